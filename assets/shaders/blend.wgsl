@@ -0,0 +1,73 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+// The effect's input.
+// The effect's output is already in the render target,
+// this shader blends the input back on top of it.
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+
+struct EffectBlend {
+    weight: f32,
+    feather: f32,
+    invert: f32,
+    // Converts UVs into the region's space.
+    space_scale: vec2<f32>,
+    center: vec2<f32>,
+    extents: vec2<f32>,
+};
+@group(0) @binding(2)
+var<uniform> blend: EffectBlend;
+
+@group(0) @binding(3)
+var mask: texture_2d<f32>;
+@group(0) @binding(4)
+var mask_sampler: sampler;
+
+// How much of the effect is applied at this UV, ignoring its weight.
+fn coverage(uv: vec2<f32>) -> f32 {
+    var coverage = 1.0;
+
+#ifdef REGION_MASK
+    coverage = textureSample(mask, mask_sampler, uv).r;
+#endif
+
+#ifdef REGION
+#ifndef REGION_MASK
+    // Signed distance to the edge of the shape, negative inside.
+    let p = uv * blend.space_scale - blend.center;
+    var distance = 0.0;
+
+#ifdef REGION_RECT
+    let q = abs(p) - blend.extents;
+    distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0);
+#endif
+#ifdef REGION_CIRCLE
+    distance = length(p) - blend.extents.x;
+#endif
+#ifdef REGION_ELLIPSE
+    distance = (length(p / blend.extents) - 1.0) * min(blend.extents.x, blend.extents.y);
+#endif
+
+    if blend.feather > 0.0 {
+        coverage = saturate(-distance / blend.feather);
+    } else {
+        coverage = select(0.0, 1.0, distance <= 0.0);
+    }
+#endif
+
+    coverage = mix(coverage, 1.0 - coverage, blend.invert);
+#endif
+
+    return coverage;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let input = textureSample(t, ts, in.uv);
+
+    // The alpha is how much of the input covers the output,
+    // so where the effect is fully applied the output is untouched.
+    return vec4<f32>(input.rgb, 1.0 - saturate(blend.weight) * coverage(in.uv));
+}
@@ -0,0 +1,34 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+// The image before the compared effects.
+// The image after them is already in the render target,
+// this shader draws the image before them on one side of the divider.
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+
+struct EffectComparison {
+    line_color: vec4<f32>,
+    // The size of the view, in pixels.
+    size: vec2<f32>,
+    // A point on the divider, in pixels.
+    position: vec2<f32>,
+    // The normal of the divider, pointing away from the image before the effects.
+    normal: vec2<f32>,
+    line_width: f32,
+};
+@group(0) @binding(2)
+var<uniform> comparison: EffectComparison;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let before = textureSample(t, ts, in.uv);
+
+    // Signed distance to the divider in pixels, negative on the side of the image before.
+    let distance = dot(in.uv * comparison.size - comparison.position, comparison.normal);
+
+    // The alpha is how much of the image before covers the image after.
+    let color = vec4<f32>(before.rgb, select(0.0, 1.0, distance < 0.0));
+    return select(color, comparison.line_color, abs(distance) <= comparison.line_width * 0.5);
+}
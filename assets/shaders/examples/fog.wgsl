@@ -0,0 +1,32 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_render::globals::Globals
+#import bevy_vfx_bag::view_bindings::{view, prepass_view_depth}
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct Fog {
+    color: vec4<f32>,
+    start: f32,
+    end: f32,
+};
+@group(1) @binding(0)
+var<uniform> fog: Fog;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t, ts, in.uv);
+
+#ifdef DEPTH_PREPASS
+    let amount = saturate((prepass_view_depth(in.uv) - fog.start) / (fog.end - fog.start));
+#else
+    // Without a depth prepass there is nothing to tell the distance by.
+    let amount = 0.0;
+#endif
+
+    return vec4<f32>(mix(color.rgb, fog.color.rgb, amount * fog.color.a), color.a);
+}
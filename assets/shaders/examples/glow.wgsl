@@ -0,0 +1,67 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_render::globals::Globals
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct Glow {
+    threshold: f32,
+    radius: f32,
+    intensity: f32,
+};
+@group(1) @binding(0)
+var<uniform> glow: Glow;
+
+// The targets the current pass reads.
+@group(2) @binding(0)
+var input: texture_2d<f32>;
+@group(2) @binding(1)
+var input_sampler: sampler;
+
+const WEIGHTS = array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+fn bright(uv: vec2<f32>) -> vec3<f32> {
+    let color = textureSample(t, ts, uv).rgb;
+    let luminance = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    return color * saturate(luminance - glow.threshold);
+}
+
+// Blurs the bright parts of the screen horizontally.
+@fragment
+fn horizontal(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let step = vec2<f32>(glow.radius / f32(textureDimensions(t).x), 0.0);
+
+    var color = bright(in.uv) * WEIGHTS[0];
+    for (var i = 1; i < 5; i++) {
+        color += bright(in.uv + step * f32(i)) * WEIGHTS[i];
+        color += bright(in.uv - step * f32(i)) * WEIGHTS[i];
+    }
+
+    return vec4<f32>(color, 1.0);
+}
+
+// Blurs the horizontal pass vertically.
+@fragment
+fn vertical(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let step = vec2<f32>(0.0, glow.radius / f32(textureDimensions(input).y));
+
+    var color = textureSample(input, input_sampler, in.uv).rgb * WEIGHTS[0];
+    for (var i = 1; i < 5; i++) {
+        color += textureSample(input, input_sampler, in.uv + step * f32(i)).rgb * WEIGHTS[i];
+        color += textureSample(input, input_sampler, in.uv - step * f32(i)).rgb * WEIGHTS[i];
+    }
+
+    return vec4<f32>(color, 1.0);
+}
+
+// Adds the blurred bright parts on top of the screen.
+@fragment
+fn composite(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t, ts, in.uv);
+    let glowing = textureSample(input, input_sampler, in.uv).rgb * glow.intensity;
+    return vec4<f32>(color.rgb + glowing, color.a);
+}
@@ -0,0 +1,80 @@
+#import bevy_render::globals::Globals
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+@group(0) @binding(3)
+var output: texture_storage_2d<rgba16float, write>;
+
+struct Kuwahara {
+    radius: i32,
+};
+@group(1) @binding(0)
+var<uniform> kuwahara: Kuwahara;
+
+// The largest supported radius.
+const MAX_RADIUS: i32 = 4;
+const TILE_X: i32 = #{WORKGROUP_SIZE_X} + 2 * MAX_RADIUS;
+const TILE_Y: i32 = #{WORKGROUP_SIZE_Y} + 2 * MAX_RADIUS;
+
+// The workgroup's pixels plus a border of MAX_RADIUS, loaded once and shared by all invocations.
+var<workgroup> tile: array<array<vec3<f32>, TILE_X>, TILE_Y>;
+
+fn load(pixel: vec2<i32>) -> vec3<f32> {
+    let size = vec2<i32>(textureDimensions(t));
+    return textureLoad(t, clamp(pixel, vec2<i32>(0), size - 1), 0).rgb;
+}
+
+@compute @workgroup_size(#{WORKGROUP_SIZE_X}, #{WORKGROUP_SIZE_Y}, 1)
+fn compute(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+) {
+    let origin = vec2<i32>(workgroup_id.xy) * vec2<i32>(#{WORKGROUP_SIZE_X}, #{WORKGROUP_SIZE_Y}) - MAX_RADIUS;
+    for (var y = i32(local_id.y); y < TILE_Y; y += #{WORKGROUP_SIZE_Y}) {
+        for (var x = i32(local_id.x); x < TILE_X; x += #{WORKGROUP_SIZE_X}) {
+            tile[y][x] = load(origin + vec2<i32>(x, y));
+        }
+    }
+    workgroupBarrier();
+
+    let size = textureDimensions(t);
+    if global_id.x >= size.x || global_id.y >= size.y {
+        return;
+    }
+
+    // Picks the mean of the quadrant around the pixel with the least variance.
+    let radius = clamp(kuwahara.radius, 1, MAX_RADIUS);
+    let center = vec2<i32>(local_id.xy) + MAX_RADIUS;
+    var best_mean = vec3<f32>(0.0);
+    var best_variance = 1e20;
+
+    for (var quadrant = 0; quadrant < 4; quadrant++) {
+        let direction = vec2<i32>(select(-1, 1, (quadrant & 1) == 1), select(-1, 1, quadrant >= 2));
+        var sum = vec3<f32>(0.0);
+        var squared = vec3<f32>(0.0);
+        for (var y = 0; y <= radius; y++) {
+            for (var x = 0; x <= radius; x++) {
+                let p = center + direction * vec2<i32>(x, y);
+                let color = tile[p.y][p.x];
+                sum += color;
+                squared += color * color;
+            }
+        }
+
+        let count = f32((radius + 1) * (radius + 1));
+        let mean = sum / count;
+        let variance = squared / count - mean * mean;
+        let total = variance.r + variance.g + variance.b;
+        if total < best_variance {
+            best_variance = total;
+            best_mean = mean;
+        }
+    }
+
+    textureStore(output, vec2<i32>(global_id.xy), vec4<f32>(best_mean, 1.0));
+}
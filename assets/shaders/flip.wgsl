@@ -1,5 +1,6 @@
 #import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
 #import bevy_render::globals::Globals
+#import bevy_vfx_bag::flip::{Flip, flip_uv}
 
 @group(0) @binding(0)
 var t: texture_2d<f32>;
@@ -8,15 +9,12 @@ var ts: sampler;
 @group(0) @binding(2)
 var<uniform> globals: Globals;
 
-struct Flip {
-    x: f32,
-    y: f32,
-};
 @group(1) @binding(0)
 var<uniform> flip: Flip;
 
 @fragment
 fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
-    let uv = abs(vec2<f32>(flip.x, flip.y) - in.uv);
+    let resolution = vec2<f32>(textureDimensions(t));
+    let uv = flip_uv(in.uv, resolution, globals.time, flip);
     return textureSample(t, ts, uv);
-}
\ No newline at end of file
+}
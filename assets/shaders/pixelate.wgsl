@@ -1,5 +1,6 @@
 #import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
 #import bevy_render::globals::Globals
+#import bevy_vfx_bag::pixelate::{Pixelate, pixelate_uv}
 
 @group(0) @binding(0)
 var t: texture_2d<f32>;
@@ -8,23 +9,13 @@ var ts: sampler;
 @group(0) @binding(2)
 var<uniform> globals: Globals;
 
-struct Pixelate {
-    block_size: f32,
-};
 @group(1) @binding(0)
 var<uniform> pixelate: Pixelate;
 
 @fragment
 fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
     let resolution = vec2<f32>(textureDimensions(t));
+    let uv = pixelate_uv(in.uv, resolution, globals.time, pixelate);
 
-    let width_height_over_block_size = resolution / max(1.0, pixelate.block_size);
-
-    var uv = in.uv + 0.5;
-    uv *= width_height_over_block_size;
-    uv = floor(uv);
-    uv /= width_height_over_block_size;
-    uv -= 0.5;
-
-    return textureSample(t, ts, uv); 
-}
\ No newline at end of file
+    return textureSample(t, ts, uv);
+}
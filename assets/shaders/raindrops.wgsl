@@ -9,8 +9,8 @@ var ts: sampler;
 var<uniform> globals: Globals;
 
 struct Raindrops {
-    time_scaling: f32,
-    intensity: f32,
+    speed: f32,
+    warping: f32,
     zoom: f32
 };
 
@@ -29,7 +29,7 @@ fn remap_raindrops_rga(
 }
 
 fn animation(raindrops_b: f32) -> f32 {
-    return fract(raindrops_b - (globals.time * raindrops.time_scaling));
+    return fract(raindrops_b - (globals.time * raindrops.speed));
 }
 
 @fragment
@@ -55,7 +55,7 @@ fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
     // Using (-1, 1) range offsets in the droplet positions
     // means the droplets would span the entire scene.
     // Thus scale it far down (by default).
-    let offset = t_raindrops_rga.rg * raindrops.intensity;
+    let offset = t_raindrops_rga.rg * raindrops.warping;
 
     let mask = (animation(t_raindrops.b) * mask_anim) + mask_static;
     let masked_norms = mask * offset;
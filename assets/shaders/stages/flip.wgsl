@@ -0,0 +1,11 @@
+#define_import_path bevy_vfx_bag::flip
+
+struct Flip {
+    x: f32,
+    y: f32,
+};
+
+// Where to sample the input for the given output UV.
+fn flip_uv(uv: vec2<f32>, resolution: vec2<f32>, time: f32, flip: Flip) -> vec2<f32> {
+    return abs(vec2<f32>(flip.x, flip.y) - uv);
+}
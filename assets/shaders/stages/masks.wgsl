@@ -0,0 +1,77 @@
+#define_import_path bevy_vfx_bag::masks
+
+struct Mask {
+    strength: f32,
+    fade: f32,
+};
+
+#ifdef SQUARE
+// A rounded square type mask.
+fn square(uv: vec2<f32>, strength: f32) -> f32 {
+    // The trick is to make the UV saturate quickly-
+    // this impacts the width of the effect.
+    // However this only creates a border in one corner.
+    // The (1 - uv) version creates the diagonally mirrored border.
+    let uv_big = saturate(uv * strength);
+    let uv_big_inv = saturate((1. - uv) * strength);
+
+    // By multiplying the mirrored borders we can get a full border.
+    let square = uv_big * uv_big_inv;
+
+    // The border is made by saturing UV coordinates.
+    // This means the border is increasingly red and green in different
+    // directions.
+    // By multiplying them together we get a single unified border.
+    let mask = square.r * square.g;
+
+    return mask;
+}
+#endif
+
+#ifdef CRT
+// Also a rounded square type mask, but more oval.
+// Reminiscent of a CRT television.
+fn crt(uv: vec2<f32>, strength: f32) -> f32 {
+    let square = uv * (1. - uv);
+    var norm = square.r * square.g;
+    norm *= norm;
+    norm *= strength;
+
+    return saturate(norm);
+}
+#endif
+
+#ifdef VIGNETTE
+// Vignette type mask.
+fn vignette(uv: vec2<f32>, strength: f32) -> f32 {
+    // Strategy is to use the UV distance from the screen's center.
+    var uv_centered = uv * 2. - 1.;
+
+    // By scaling this we can adjust how bright/dark the vignette is.
+    uv_centered *= strength;
+
+    let zero = vec2<f32>(0.);
+
+    var dist = saturate(distance(zero, uv_centered));
+    dist = pow(dist, 1.5);
+    dist = 1. - dist;
+    dist += 0.05;
+
+    return saturate(dist);
+}
+#endif
+
+// The masked color of the input at the given UV.
+fn mask_color(color: vec4<f32>, uv: vec2<f32>, resolution: vec2<f32>, time: f32, mask: Mask) -> vec4<f32> {
+    #ifdef SQUARE
+    let result = square(uv, mask.strength);
+    #endif
+    #ifdef CRT
+    let result = crt(uv, mask.strength);
+    #endif
+    #ifdef VIGNETTE
+    let result = vignette(uv, mask.strength);
+    #endif
+
+    return vec4<f32>(color.rgb * saturate(result + mask.fade), 1.0);
+}
@@ -0,0 +1,18 @@
+#define_import_path bevy_vfx_bag::pixelate
+
+struct Pixelate {
+    block_size: f32,
+};
+
+// Where to sample the input for the given output UV.
+fn pixelate_uv(uv_in: vec2<f32>, resolution: vec2<f32>, time: f32, pixelate: Pixelate) -> vec2<f32> {
+    let width_height_over_block_size = resolution / max(1.0, pixelate.block_size);
+
+    var uv = uv_in + 0.5;
+    uv *= width_height_over_block_size;
+    uv = floor(uv);
+    uv /= width_height_over_block_size;
+    uv -= 0.5;
+
+    return uv;
+}
@@ -0,0 +1,25 @@
+#define_import_path bevy_vfx_bag::wave
+
+#import bevy_render::maths::PI
+
+struct Wave {
+    waves_x: f32,
+    waves_y: f32,
+
+    speed_x: f32,
+    speed_y: f32,
+
+    amplitude_x: f32,
+    amplitude_y: f32
+};
+
+// Where to sample the input for the given output UV.
+fn wave_uv(uv: vec2<f32>, resolution: vec2<f32>, time: f32, wave: Wave) -> vec2<f32> {
+    let pi_uv = PI * uv;
+    let pi_time = PI * time;
+
+    let offset_x = sin((pi_uv.y * wave.waves_x) + (pi_time * wave.speed_x)) * wave.amplitude_x;
+    let offset_y = sin((pi_uv.x * wave.waves_y) + (pi_time * wave.speed_y)) * wave.amplitude_y;
+
+    return vec2<f32>(uv.x + offset_x, uv.y + offset_y);
+}
@@ -0,0 +1,83 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_render::globals::Globals
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct Transition {
+    color: vec4<f32>,
+    edge_color: vec4<f32>,
+    direction: vec2<f32>,
+    center: vec2<f32>,
+    progress: f32,
+    softness: f32,
+    edge_width: f32,
+    tile_size: f32,
+    noise_scale: f32,
+};
+
+@group(1) @binding(0)
+var t_noise: texture_2d<f32>;
+@group(1) @binding(1)
+var ts_noise: sampler;
+@group(1) @binding(2)
+var<uniform> transition: Transition;
+
+// How late in the transition a pixel is covered, from 0 to 1.
+fn threshold(uv: vec2<f32>, resolution: vec2<f32>) -> f32 {
+#ifdef WIPE
+    // Project the screen corners on the direction, to cover the screen exactly.
+    let direction = normalize(transition.direction);
+    let extent = abs(direction.x) + abs(direction.y);
+    return (dot(uv - 0.5, direction) + 0.5 * extent) / extent;
+#else ifdef IRIS
+    // Aspect corrected, so the iris is a circle.
+    let aspect = vec2<f32>(resolution.x / resolution.y, 1.);
+    let offset = (uv - transition.center) * aspect;
+    let farthest = max(transition.center, 1. - transition.center) * aspect;
+    return 1. - length(offset) / length(farthest);
+#else ifdef TILES
+    let pixel = uv * resolution;
+    let local = fract(pixel / transition.tile_size) - 0.5;
+#ifdef TILE_DIAMOND
+    return abs(local.x) + abs(local.y);
+#else
+    return length(local) / sqrt(0.5);
+#endif
+#else ifdef DISSOLVE
+    let aspect = vec2<f32>(resolution.x / resolution.y, 1.);
+    return textureSample(t_noise, ts_noise, uv * aspect * transition.noise_scale).r;
+#else
+    return 0.;
+#endif
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let sample = textureSample(t, ts, in.uv);
+
+#ifdef FADE
+    return vec4<f32>(mix(sample.rgb, transition.color.rgb, transition.progress * transition.color.a), 1.0);
+#else
+    let resolution = vec2<f32>(textureDimensions(t));
+    let value = threshold(in.uv, resolution);
+
+    // The edge runs from before the first to past the last pixel,
+    // so nothing is covered at progress 0 and everything at 1.
+    let softness = max(transition.softness, 0.0001);
+    let edge = transition.progress * (1. + softness + transition.edge_width) - transition.edge_width;
+    let covered = 1. - smoothstep(edge - softness, edge, value);
+
+    var color = sample.rgb;
+    if transition.edge_width > 0. {
+        let glow = 1. - smoothstep(edge, edge + transition.edge_width, value);
+        color = mix(color, transition.edge_color.rgb, glow * transition.edge_color.a);
+    }
+
+    return vec4<f32>(mix(color, transition.color.rgb, covered * transition.color.a), 1.0);
+#endif
+}
@@ -0,0 +1,56 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+// The effect's output, at a reduced resolution.
+@group(0) @binding(0)
+var low_res: texture_2d<f32>;
+@group(0) @binding(1)
+var low_res_sampler: sampler;
+
+#ifdef EDGE_AWARE
+// The effect's input at full resolution, which guides the upsampling.
+@group(0) @binding(2)
+var guide: texture_2d<f32>;
+
+fn luminance(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+// How similar two luminances are, relative to their brightness so it works for HDR too.
+fn similarity(a: f32, b: f32) -> f32 {
+    let difference = abs(a - b) / (max(a, b) + 0.001);
+    return exp(-16.0 * difference);
+}
+#endif
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+#ifdef EDGE_AWARE
+    // A joint bilateral upsample: the four nearest low resolution texels are weighted
+    // bilinearly, and by how similar the input there is to the input at this pixel.
+    // This keeps the effect from bleeding across edges of the input.
+    let size = vec2<f32>(textureDimensions(low_res));
+    let texel = in.uv * size - 0.5;
+    let base = floor(texel);
+    let f = fract(texel);
+
+    let center = luminance(textureSampleLevel(guide, low_res_sampler, in.uv, 0.0).rgb);
+
+    var color = vec4<f32>(0.0);
+    var total = 0.0;
+    for (var y = 0; y < 2; y++) {
+        for (var x = 0; x < 2; x++) {
+            let uv = (base + vec2<f32>(f32(x), f32(y)) + 0.5) / size;
+            let bilinear = select(1.0 - f.x, f.x, x == 1) * select(1.0 - f.y, f.y, y == 1);
+            let guided = luminance(textureSampleLevel(guide, low_res_sampler, uv, 0.0).rgb);
+            let weight = bilinear * similarity(center, guided) + 0.0001;
+
+            color += textureSampleLevel(low_res, low_res_sampler, uv, 0.0) * weight;
+            total += weight;
+        }
+    }
+
+    return color / total;
+#else
+    return textureSample(low_res, low_res_sampler, in.uv);
+#endif
+}
@@ -0,0 +1,78 @@
+#define_import_path bevy_vfx_bag::view_bindings
+
+#import bevy_render::view::View
+
+// The bindings of effects reading the view.
+// Prepass textures are only bound when the camera has the matching prepass.
+@group(#{VIEW_BINDINGS_GROUP}) @binding(0)
+var<uniform> view: View;
+
+#ifdef DEPTH_PREPASS
+#ifdef MULTISAMPLED
+@group(#{VIEW_BINDINGS_GROUP}) @binding(1)
+var depth_prepass_texture: texture_depth_multisampled_2d;
+#else
+@group(#{VIEW_BINDINGS_GROUP}) @binding(1)
+var depth_prepass_texture: texture_depth_2d;
+#endif
+#endif
+
+#ifdef NORMAL_PREPASS
+#ifdef MULTISAMPLED
+@group(#{VIEW_BINDINGS_GROUP}) @binding(2)
+var normal_prepass_texture: texture_multisampled_2d<f32>;
+#else
+@group(#{VIEW_BINDINGS_GROUP}) @binding(2)
+var normal_prepass_texture: texture_2d<f32>;
+#endif
+#endif
+
+#ifdef MOTION_VECTOR_PREPASS
+#ifdef MULTISAMPLED
+@group(#{VIEW_BINDINGS_GROUP}) @binding(3)
+var motion_vector_prepass_texture: texture_multisampled_2d<f32>;
+#else
+@group(#{VIEW_BINDINGS_GROUP}) @binding(3)
+var motion_vector_prepass_texture: texture_2d<f32>;
+#endif
+#endif
+
+// The pixel of the view at the given UV.
+fn view_pixel(uv: vec2<f32>) -> vec2<i32> {
+    return vec2<i32>(uv * view.viewport.zw);
+}
+
+#ifdef DEPTH_PREPASS
+// The prepass depth at the given UV, in reverse Z: 1.0 is the near plane, 0.0 infinitely far.
+fn prepass_depth(uv: vec2<f32>) -> f32 {
+    return textureLoad(depth_prepass_texture, view_pixel(uv), 0);
+}
+
+// The world position of the prepass depth at the given UV.
+fn prepass_world_position(uv: vec2<f32>) -> vec3<f32> {
+    let ndc = vec3<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, prepass_depth(uv));
+    let world = view.world_from_clip * vec4<f32>(ndc, 1.0);
+    return world.xyz / world.w;
+}
+
+// The distance along the view direction of the prepass depth at the given UV.
+fn prepass_view_depth(uv: vec2<f32>) -> f32 {
+    let ndc = vec3<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, prepass_depth(uv));
+    let position = view.view_from_clip * vec4<f32>(ndc, 1.0);
+    return -position.z / position.w;
+}
+#endif
+
+#ifdef NORMAL_PREPASS
+// The world space normal at the given UV.
+fn prepass_normal(uv: vec2<f32>) -> vec3<f32> {
+    return normalize(textureLoad(normal_prepass_texture, view_pixel(uv), 0).xyz * 2.0 - 1.0);
+}
+#endif
+
+#ifdef MOTION_VECTOR_PREPASS
+// The screen space motion since the last frame at the given UV, in UV units.
+fn prepass_motion_vector(uv: vec2<f32>) -> vec2<f32> {
+    return textureLoad(motion_vector_prepass_texture, view_pixel(uv), 0).xy;
+}
+#endif
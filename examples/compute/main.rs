@@ -0,0 +1,70 @@
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::{
+    prelude::*,
+    render::{
+        extract_component::ExtractComponent, render_graph::RenderLabel, render_resource::ShaderType,
+    },
+};
+use bevy_vfx_bag::{
+    post_processing::compute::{ComputePostProcess, ComputePostProcessPlugin},
+    BevyVfxBagPlugin,
+};
+
+/// A Kuwahara filter, giving the screen a painted look.
+///
+/// Each workgroup loads its pixels into workgroup memory once,
+/// instead of every pixel sampling its whole neighbourhood.
+#[derive(Debug, Component, ExtractComponent, Clone, Copy, ShaderType)]
+struct Kuwahara {
+    radius: i32,
+}
+
+impl Default for Kuwahara {
+    fn default() -> Self {
+        Self { radius: 4 }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
+struct KuwaharaLabel;
+
+impl ComputePostProcess for Kuwahara {
+    type Label = KuwaharaLabel;
+
+    fn shader_path() -> String {
+        "shaders/examples/kuwahara.wgsl".into()
+    }
+}
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(examples_common::SaneDefaultsPlugin)
+        .add_plugins(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugins(BevyVfxBagPlugin)
+        .add_plugins(ComputePostProcessPlugin::<Kuwahara>::default())
+        .add_systems(Startup, setup)
+        .add_systems(FixedUpdate, switch)
+        .insert_resource(Time::<Fixed>::from_seconds(1.5))
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    info!("Applies a Kuwahara filter in a compute shader, cycling its radius every interval.");
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 7., 14.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+        Kuwahara::default(),
+    ));
+}
+
+// Cycle the filter's radius.
+fn switch(mut query: Query<&mut Kuwahara>) {
+    for mut kuwahara in &mut query {
+        kuwahara.radius = kuwahara.radius % 4 + 1;
+        info!("Radius: {}", kuwahara.radius);
+    }
+}
@@ -0,0 +1,79 @@
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::{
+    core_pipeline::prepass::DepthPrepass,
+    prelude::*,
+    render::{
+        extract_component::ExtractComponent, render_graph::RenderLabel, render_resource::ShaderType,
+    },
+};
+use bevy_vfx_bag::{
+    post_processing::simple_post_process::{SimplePostProcess, SimplePostProcessPlugin},
+    BevyVfxBagPlugin,
+};
+
+/// Fades the scene into a color with distance, read from the camera's depth prepass.
+#[derive(Debug, Component, ExtractComponent, Clone, Copy, ShaderType)]
+struct Fog {
+    color: LinearRgba,
+    start: f32,
+    end: f32,
+}
+
+impl Default for Fog {
+    fn default() -> Self {
+        Self {
+            color: LinearRgba::new(0.5, 0.55, 0.6, 1.0),
+            start: 10.0,
+            end: 25.0,
+        }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
+struct FogLabel;
+
+impl SimplePostProcess for Fog {
+    type Label = FogLabel;
+
+    fn shader_path() -> String {
+        "shaders/examples/fog.wgsl".into()
+    }
+
+    fn view_bindings() -> bool {
+        true
+    }
+}
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(examples_common::SaneDefaultsPlugin)
+        .add_plugins(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugins(BevyVfxBagPlugin)
+        .add_plugins(SimplePostProcessPlugin::<Fog>::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, move_camera)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    info!("Fades the scene into fog with distance, using the depth prepass.");
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 7., 14.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+        DepthPrepass,
+        Fog::default(),
+    ));
+}
+
+// Move the camera back and forth, so the shapes move in and out of the fog.
+fn move_camera(time: Res<Time>, mut query: Query<&mut Transform, With<Fog>>) {
+    for mut transform in &mut query {
+        let distance = 14.0 + time.elapsed_secs().sin() * 8.0;
+        *transform = Transform::from_xyz(0.0, distance * 0.5, distance)
+            .looking_at(Vec3::new(0., 1., 0.), Vec3::Y);
+    }
+}
@@ -0,0 +1,82 @@
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{
+        chromatic_aberration::ChromaticAberration,
+        impulse::{EffectImpulse, ImpulseEnvelope},
+        masks::Mask,
+        wave::Wave,
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(examples_common::SaneDefaultsPlugin)
+        .add_plugins(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugins(BevyVfxBagPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(Update, fire)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    info!("Press 1 for a chromatic aberration spike, 2 for a wave burst and 3 for a vignette pulse. Press repeatedly to stack them.");
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 7., 14.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+    ));
+}
+
+fn fire(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    camera: Query<Entity, With<Camera>>,
+    mut aberrations: EventWriter<EffectImpulse<ChromaticAberration>>,
+    mut waves: EventWriter<EffectImpulse<Wave>>,
+    mut masks: EventWriter<EffectImpulse<Mask>>,
+) {
+    let camera = camera.single();
+
+    if keyboard_input.just_pressed(KeyCode::Digit1) {
+        aberrations.send(EffectImpulse::new(
+            camera,
+            ChromaticAberration {
+                magnitude_r: 0.01,
+                magnitude_g: 0.01,
+                magnitude_b: 0.01,
+                ..default()
+            },
+            ImpulseEnvelope::hit(0.3),
+        ));
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Digit2) {
+        waves.send(EffectImpulse::new(
+            camera,
+            Wave {
+                waves_x: 3.,
+                waves_y: 3.,
+                speed_x: 20.,
+                speed_y: 25.,
+                amplitude_x: 0.01,
+                amplitude_y: 0.01,
+            },
+            ImpulseEnvelope::new(0.05, 0.1, 0.4),
+        ));
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Digit3) {
+        masks.send(EffectImpulse::new(
+            camera,
+            Mask {
+                strength: 1.5,
+                ..Mask::vignette()
+            },
+            ImpulseEnvelope::new(0.1, 0.2, 0.5).with_ease(EaseFunction::CubicInOut),
+        ));
+    }
+}
@@ -1,9 +1,12 @@
 #[path = "../examples_common.rs"]
 mod examples_common;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_vfx_bag::{
-    post_processing::lut::{Lut, LutPostProcessBindGroup},
+    post_processing::{
+        comparison::EffectComparison,
+        lut::{Lut, LutPostProcessBindGroup, LutPostProcessLabel},
+    },
     BevyVfxBagPlugin,
 };
 
@@ -14,20 +17,32 @@ fn main() {
         .add_plugins(examples_common::ShapesExamplePlugin::without_3d_camera())
         .add_plugins(BevyVfxBagPlugin)
         .add_systems(Startup, setup)
-        .add_systems(Update, change)
+        .add_systems(Update, (change, move_divider))
         .run();
 }
 
 fn setup(mut commands: Commands, mut bind_group_asset: ResMut<Assets<LutPostProcessBindGroup>>) {
     info!("Press [left|right] to change which LUT is in use");
+    info!("Move the cursor to compare with the image before color grading");
 
     commands.spawn((
         Camera3d::default(),
         Transform::from_xyz(0.0, 7., 14.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
         Lut::neo(&mut bind_group_asset),
+        EffectComparison::effect(LutPostProcessLabel),
     ));
 }
 
+// The image before color grading is shown left of the cursor.
+fn move_divider(
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut comparison: Single<&mut EffectComparison>,
+) {
+    if let Some(cursor) = window.cursor_position() {
+        comparison.position = cursor / window.size();
+    }
+}
+
 // Cycle through some preset LUTs.
 fn change(
     mut choice: Local<usize>,
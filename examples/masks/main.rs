@@ -3,10 +3,7 @@ mod examples_common;
 
 use bevy::prelude::*;
 use bevy_vfx_bag::{
-    post_processing::{
-        masks::{Mask, MaskVariant},
-        simple_post_process::PostProcessShaderDef,
-    },
+    post_processing::masks::{Mask, MaskVariant},
     BevyVfxBagPlugin,
 };
 
@@ -32,25 +29,18 @@ fn setup(mut commands: Commands) {
     ));
 }
 
-fn change(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut query: Query<&mut Mask, With<Camera>>,
-    mut shader_def: ResMut<PostProcessShaderDef<Mask>>,
-) {
+fn change(keyboard_input: Res<ButtonInput<KeyCode>>, mut query: Query<&mut Mask, With<Camera>>) {
     let mut mask = query.single_mut();
 
     if keyboard_input.just_pressed(KeyCode::Digit1) {
         *mask = Mask::square();
-        shader_def.set_shader_defs(vec![MaskVariant::Square.into()]);
     } else if keyboard_input.just_pressed(KeyCode::Digit2) {
         *mask = Mask::crt();
-        shader_def.set_shader_defs(vec![MaskVariant::Crt.into()]);
     } else if keyboard_input.just_pressed(KeyCode::Digit3) {
         *mask = Mask::vignette();
-        shader_def.set_shader_defs(vec![MaskVariant::Vignette.into()]);
     }
 
-    let shader_defined = shader_def.shader_defs().iter().next().unwrap().into();
+    let shader_defined = mask.variant;
 
     // Let user change strength in increments via up, down arrows
     let increment = || match shader_defined {
@@ -0,0 +1,93 @@
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::{
+    prelude::*,
+    render::{
+        extract_component::ExtractComponent, render_graph::RenderLabel, render_resource::ShaderType,
+    },
+};
+use bevy_vfx_bag::{
+    post_processing::multi_pass::{
+        EffectPass, EffectTarget, MultiPassPostProcess, MultiPassPostProcessPlugin, TargetSize,
+    },
+    BevyVfxBagPlugin,
+};
+
+/// Makes bright parts of the screen glow.
+///
+/// The bright parts are blurred horizontally into a half resolution target,
+/// then vertically into another, which is finally added on top of the screen.
+#[derive(Debug, Component, ExtractComponent, Clone, Copy, ShaderType)]
+struct Glow {
+    threshold: f32,
+    radius: f32,
+    intensity: f32,
+}
+
+impl Default for Glow {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            radius: 2.0,
+            intensity: 1.5,
+        }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
+struct GlowLabel;
+
+impl MultiPassPostProcess for Glow {
+    type Label = GlowLabel;
+
+    fn shader_path() -> String {
+        "shaders/examples/glow.wgsl".into()
+    }
+
+    fn targets() -> Vec<EffectTarget> {
+        vec![
+            EffectTarget::new("horizontal").with_size(TargetSize::Scale(0.5)),
+            EffectTarget::new("vertical").with_size(TargetSize::Scale(0.5)),
+        ]
+    }
+
+    fn passes() -> Vec<EffectPass> {
+        vec![
+            EffectPass::new("horizontal").writing("horizontal"),
+            EffectPass::new("vertical")
+                .reading("horizontal")
+                .writing("vertical"),
+            EffectPass::new("composite").reading("vertical"),
+        ]
+    }
+}
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(examples_common::SaneDefaultsPlugin)
+        .add_plugins(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugins(BevyVfxBagPlugin)
+        .add_plugins(MultiPassPostProcessPlugin::<Glow>::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, pulse)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    info!("Makes bright parts of the screen glow, using three passes.");
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 7., 14.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+        Glow::default(),
+    ));
+}
+
+// Pulse the glow's radius.
+fn pulse(time: Res<Time>, mut query: Query<&mut Glow>) {
+    for mut glow in &mut query {
+        glow.radius = 2.0 + time.elapsed_secs().sin() * 1.5;
+    }
+}
@@ -0,0 +1,106 @@
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::{
+    prelude::*,
+    render::camera::RenderTarget,
+    window::{Window, WindowRef},
+};
+
+use bevy_vfx_bag::{
+    post_processing::{
+        blur::{Blur, BlurPostProcessLabel},
+        chromatic_aberration::{ChromaticAberration, ChromaticAberrationPostProcessLabel},
+        lut::{Lut, LutPostProcessBindGroup, LutPostProcessLabel},
+        masks::{Mask, MaskPostProcessLabel},
+        pixelate::{Pixelate, PixelatePostProcessLabel},
+        raindrops::{Raindrops, RaindropsPostProcessLabel},
+        stack::PostProcessingOrder,
+        wave::{Wave, WavePostProcessLabel},
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(examples_common::SaneDefaultsPlugin)
+        .add_plugins(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugins(BevyVfxBagPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(Update, rotate_order);
+
+    app.run();
+}
+
+fn setup(mut commands: Commands, mut bind_group_asset: ResMut<Assets<LutPostProcessBindGroup>>) {
+    info!("Press space to rotate the effect order of the second window.");
+
+    let transform = Transform::from_xyz(-5.0, 12., 10.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y);
+
+    // First window: Clean, no effects
+    commands.spawn((Camera3d::default(), transform));
+
+    // Second window: Camera has effects
+    let window_2 = commands.spawn(Window::default()).id();
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            target: RenderTarget::Window(WindowRef::Entity(window_2)),
+            ..default()
+        },
+        transform,
+        Wave {
+            waves_x: 3.,
+            speed_x: 0.5,
+            amplitude_x: 0.02,
+            ..default()
+        },
+        Pixelate::default(),
+        Mask::default(),
+        Lut::neo(&mut bind_group_asset),
+        Blur::default(),
+        PostProcessingOrder::new()
+            .then(WavePostProcessLabel)
+            .then(PixelatePostProcessLabel)
+            .then(MaskPostProcessLabel)
+            .then(LutPostProcessLabel)
+            .then(BlurPostProcessLabel),
+    ));
+
+    // Third window: HDR camera has other effects
+    let window_3 = commands.spawn(Window::default()).id();
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            target: RenderTarget::Window(WindowRef::Entity(window_3)),
+            hdr: true,
+            ..default()
+        },
+        transform,
+        Mask::crt(),
+        Lut::arctic(&mut bind_group_asset),
+        ChromaticAberration::default(),
+        Raindrops::default(),
+        PostProcessingOrder::new()
+            .then(MaskPostProcessLabel)
+            .then(LutPostProcessLabel)
+            .then(ChromaticAberrationPostProcessLabel)
+            .then(RaindropsPostProcessLabel),
+    ));
+}
+
+// Move the first effect of each ordered camera to the back of its stack.
+fn rotate_order(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut query: Query<&mut PostProcessingOrder>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    for mut order in &mut query {
+        order.labels_mut().rotate_left(1);
+        info!("New order: {:?}", order.labels());
+    }
+}
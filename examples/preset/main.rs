@@ -0,0 +1,27 @@
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{post_processing::preset::VfxStackHandle, BevyVfxBagPlugin};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(examples_common::SaneDefaultsPlugin)
+        .add_plugins(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugins(BevyVfxBagPlugin)
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Hot reloading needs Bevy's `file_watcher` feature:
+    // `cargo run --example preset --features presets,bevy/file_watcher`
+    info!("Applies the effects in assets/presets/underwater.vfx.ron, edit it to see changes live.");
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 6., 12.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+        VfxStackHandle(asset_server.load("presets/underwater.vfx.ron")),
+    ));
+}
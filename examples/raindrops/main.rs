@@ -2,7 +2,10 @@
 mod examples_common;
 
 use bevy::{input::mouse::MouseWheel, prelude::*};
-use bevy_vfx_bag::{post_processing::raindrops::Raindrops, BevyVfxBagPlugin};
+use bevy_vfx_bag::{
+    post_processing::{clock::EffectClock, raindrops::Raindrops},
+    BevyVfxBagPlugin,
+};
 
 fn main() {
     let mut app = App::new();
@@ -23,15 +26,20 @@ fn setup(mut commands: Commands) {
         Camera3d::default(),
         Transform::from_xyz(0.0, 7., 14.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
         Raindrops::default(),
+        EffectClock::default(),
     ));
 }
 
 fn change(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut mouse_wheel_events: EventReader<MouseWheel>,
-    mut query: Query<&mut Raindrops, With<Camera>>,
+    mut query: Query<(&mut Raindrops, &mut EffectClock), With<Camera>>,
 ) {
-    let mut raindrops = query.single_mut();
+    let (mut raindrops, mut clock) = query.single_mut();
+
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        clock.paused = !clock.paused;
+    }
 
     if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
         raindrops.speed -= 0.1;
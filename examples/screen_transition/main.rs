@@ -0,0 +1,119 @@
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor},
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use bevy_vfx_bag::{
+    post_processing::{
+        screen_transition::{TileShape, Transition},
+        tween::EffectTween,
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(examples_common::SaneDefaultsPlugin)
+        .add_plugins(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugins(BevyVfxBagPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(Update, play)
+        .run();
+}
+
+#[derive(Resource)]
+struct Noise(Handle<Image>);
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    info!("Press 1 to fade, 2 to wipe, 3 for an iris, 4 and 5 for tiles and 6 to dissolve.");
+
+    commands.insert_resource(Noise(images.add(value_noise(256, 16))));
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 7., 14.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+    ));
+}
+
+fn play(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    noise: Res<Noise>,
+    camera: Query<Entity, With<Camera>>,
+) {
+    let transition = if keyboard_input.just_pressed(KeyCode::Digit1) {
+        Transition::fade()
+    } else if keyboard_input.just_pressed(KeyCode::Digit2) {
+        Transition::wipe(Vec2::new(1.0, 0.3)).with_edge_glow(LinearRgba::WHITE, 0.03)
+    } else if keyboard_input.just_pressed(KeyCode::Digit3) {
+        Transition::iris(Vec2::new(0.5, 0.45))
+    } else if keyboard_input.just_pressed(KeyCode::Digit4) {
+        Transition::tiles(TileShape::Diamond, 64.0)
+    } else if keyboard_input.just_pressed(KeyCode::Digit5) {
+        Transition::tiles(TileShape::Circle, 48.0).with_color(LinearRgba::rgb(0.1, 0.0, 0.2))
+    } else if keyboard_input.just_pressed(KeyCode::Digit6) {
+        Transition::dissolve(noise.0.clone()).with_edge_glow(LinearRgba::rgb(4.0, 1.5, 0.3), 0.06)
+    } else {
+        return;
+    };
+
+    // Cover the screen, then reveal it again.
+    let covered = transition.clone().with_progress(1.0);
+    commands.entity(camera.single()).insert((
+        transition.clone(),
+        EffectTween::new(transition.clone())
+            .then(covered, 0.8, EaseFunction::CubicIn)
+            .then(transition, 0.8, EaseFunction::CubicOut),
+    ));
+}
+
+/// A tileable value noise texture of the given size, with `cells` noise cells across.
+fn value_noise(size: u32, cells: u32) -> Image {
+    let lattice = |x: u32, y: u32| {
+        let mut hash =
+            (x % cells).wrapping_mul(0x27d4_eb2d) ^ (y % cells).wrapping_mul(0x1656_67b1);
+        hash ^= hash >> 15;
+        hash = hash.wrapping_mul(0x2c1b_3c6d);
+        hash ^= hash >> 12;
+        (hash & 0xffff) as f32 / 65535.0
+    };
+
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let position = Vec2::new(x as f32, y as f32) * cells as f32 / size as f32;
+            let cell = position.floor();
+            let fraction = position - cell;
+            let smooth = fraction * fraction * (3.0 - 2.0 * fraction);
+            let (cx, cy) = (cell.x as u32, cell.y as u32);
+
+            let top = lattice(cx, cy).lerp(lattice(cx + 1, cy), smooth.x);
+            let bottom = lattice(cx, cy + 1).lerp(lattice(cx + 1, cy + 1), smooth.x);
+            let value = (top.lerp(bottom, smooth.y) * 255.0) as u8;
+
+            data.extend([value, value, value, 255]);
+        }
+    }
+
+    let mut image = Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        ..ImageSamplerDescriptor::linear()
+    });
+    image
+}
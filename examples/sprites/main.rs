@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{masks::Mask, pixelate::Pixelate, wave::Wave},
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(DefaultPlugins)
+        .add_plugins(BevyVfxBagPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(Update, rotate)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    info!("Shows effects applied to a 2D camera.");
+
+    commands.spawn((
+        Camera2d,
+        Pixelate { block_size: 4.0 },
+        Wave {
+            waves_x: 2.,
+            speed_x: 0.4,
+            amplitude_x: 0.01,
+            ..default()
+        },
+        Mask::vignette(),
+    ));
+
+    let shapes = [
+        meshes.add(Circle::new(50.0)),
+        meshes.add(Rectangle::new(100.0, 100.0)),
+        meshes.add(RegularPolygon::new(50.0, 6)),
+        meshes.add(Annulus::new(25.0, 50.0)),
+    ];
+    let num_shapes = shapes.len();
+
+    for (i, shape) in shapes.into_iter().enumerate() {
+        let color = Color::hsl(360. * i as f32 / num_shapes as f32, 0.95, 0.7);
+
+        commands.spawn((
+            Mesh2d(shape),
+            MeshMaterial2d(materials.add(color)),
+            Transform::from_xyz(-300. + i as f32 * 200., 0.0, 0.0),
+            Rotates,
+        ));
+    }
+}
+
+#[derive(Component)]
+struct Rotates;
+
+fn rotate(mut query: Query<&mut Transform, With<Rotates>>, time: Res<Time>) {
+    for mut transform in &mut query {
+        transform.rotate_z(time.delta_secs() / 2.);
+    }
+}
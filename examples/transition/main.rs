@@ -0,0 +1,87 @@
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{
+        blur::Blur,
+        chromatic_aberration::ChromaticAberration,
+        lut::{Lut, LutPostProcessBindGroup},
+        masks::Mask,
+        raindrops::Raindrops,
+        transition::{StackTransition, StackTransitionCompleted},
+        wave::Wave,
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(examples_common::SaneDefaultsPlugin)
+        .add_plugins(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugins(BevyVfxBagPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(Update, (change, log_completed))
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    info!("Press 1 for the normal view, 2 to go underwater and 3 to open the menu.");
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 7., 14.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+    ));
+}
+
+fn change(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut luts: ResMut<Assets<LutPostProcessBindGroup>>,
+    camera: Query<Entity, With<Camera>>,
+) {
+    let transition = if keyboard_input.just_pressed(KeyCode::Digit1) {
+        StackTransition::new(1.0)
+    } else if keyboard_input.just_pressed(KeyCode::Digit2) {
+        StackTransition::new(2.0)
+            .with(Raindrops::default())
+            .with(ChromaticAberration {
+                magnitude_r: 0.003,
+                magnitude_g: 0.003,
+                magnitude_b: 0.003,
+                ..default()
+            })
+            .with(Wave {
+                waves_x: 1.,
+                speed_x: 0.1,
+                amplitude_x: 0.07,
+                waves_y: 10.,
+                speed_y: 0.3,
+                amplitude_y: 0.01,
+            })
+            .with(Lut::arctic(&mut luts))
+            .with(Mask::vignette())
+    } else if keyboard_input.just_pressed(KeyCode::Digit3) {
+        StackTransition::new(0.5)
+            .with(Blur {
+                amount: 1.0,
+                ..default()
+            })
+            .with(Mask {
+                strength: 1.0,
+                ..Mask::vignette()
+            })
+            .with_ease(EaseFunction::CubicOut)
+    } else {
+        return;
+    };
+
+    commands.entity(camera.single()).insert(transition);
+}
+
+fn log_completed(mut completed: EventReader<StackTransitionCompleted>) {
+    for event in completed.read() {
+        info!("Transition on {} done", event.camera);
+    }
+}
@@ -0,0 +1,104 @@
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{
+        blur::Blur,
+        tween::{EffectTween, EffectTweenCompleted},
+        wave::Wave,
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugins(examples_common::SaneDefaultsPlugin)
+        .add_plugins(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugins(BevyVfxBagPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(Update, (pause, burst, log_bursts))
+        .run();
+}
+
+fn unblurred() -> Blur {
+    Blur {
+        amount: 0.0,
+        ..default()
+    }
+}
+
+fn calm() -> Wave {
+    Wave {
+        waves_x: 4.,
+        waves_y: 4.,
+        speed_x: 1.,
+        speed_y: 1.,
+        ..default()
+    }
+}
+
+fn setup(mut commands: Commands) {
+    info!("Press P to fade to blur as if paused, and Space for a wave burst.");
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 7., 14.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+        unblurred(),
+        calm(),
+    ));
+}
+
+fn pause(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut paused: Local<bool>,
+    query: Query<(Entity, &Blur), With<Camera>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    *paused = !*paused;
+
+    let (entity, blur) = query.single();
+    let target = match *paused {
+        true => Blur::default(),
+        false => unblurred(),
+    };
+
+    // Starts from the current blur, so toggling mid-fade does not jump.
+    commands.entity(entity).insert(EffectTween::new(*blur).then(
+        target,
+        0.4,
+        EaseFunction::CubicOut,
+    ));
+}
+
+fn burst(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    query: Query<Entity, With<Camera>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    let strong = Wave {
+        amplitude_x: 0.05,
+        amplitude_y: 0.05,
+        ..calm()
+    };
+
+    commands.entity(query.single()).insert(
+        EffectTween::new(calm())
+            .then(strong, 0.15, EaseFunction::QuadraticOut)
+            .then(calm(), 0.6, EaseFunction::ElasticOut),
+    );
+}
+
+fn log_bursts(mut completed: EventReader<EffectTweenCompleted<Wave>>) {
+    for event in completed.read() {
+        info!("Wave burst on {} finished", event.entity);
+    }
+}
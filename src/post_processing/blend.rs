@@ -0,0 +1,568 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_asset::RenderAssets,
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice, RenderQueue},
+        texture::{FallbackImage, GpuImage},
+        view::ViewTarget,
+        Render, RenderApp, RenderSet,
+    },
+};
+
+use super::shader::embedded_shader_path;
+
+/// How strongly the effect `T` is applied on a camera.
+///
+/// The effect's output is blended with its input, so `0.0` leaves the image untouched
+/// and `1.0` applies the full effect. This works for every effect, which means
+/// any effect may be faded in and out by animating the weight.
+///
+/// Effects without this component are fully applied.
+///
+/// ```rust,ignore
+/// commands.spawn((
+///     Camera3d::default(),
+///     Wave::default(),
+///     EffectWeight::<Wave>::new(0.5),
+/// ));
+/// ```
+#[derive(Debug, Component)]
+pub struct EffectWeight<T> {
+    /// The weight, from `0.0` to `1.0`.
+    pub weight: f32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> EffectWeight<T> {
+    /// Applies the effect with the given weight.
+    pub fn new(weight: f32) -> Self {
+        Self {
+            weight,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for EffectWeight<T> {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl<T> Clone for EffectWeight<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for EffectWeight<T> {}
+
+impl<T: 'static> ExtractComponent for EffectWeight<T> {
+    type QueryData = &'static Self;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(weight: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        Some(*weight)
+    }
+}
+
+/// Where on the screen the effect `T` is applied on a camera.
+///
+/// Outside the region the effect's input is shown unchanged.
+/// Effects without this component cover the whole screen.
+///
+/// ```rust,ignore
+/// // Pixelate only the inside of a scope.
+/// commands.spawn((
+///     Camera3d::default(),
+///     Pixelate::default(),
+///     EffectRegion::<Pixelate>::new(RegionShape::Circle {
+///         center: Vec2::new(640.0, 360.0),
+///         radius: 200.0,
+///     })
+///     .in_pixels()
+///     .with_feather(8.0),
+/// ));
+///
+/// // Blur only the letterbox bars.
+/// commands.spawn((
+///     Camera3d::default(),
+///     Blur::default(),
+///     EffectRegion::<Blur>::new(RegionShape::Rect {
+///         min: Vec2::new(0.0, 0.1),
+///         max: Vec2::new(1.0, 0.9),
+///     })
+///     .inverted(),
+/// ));
+/// ```
+#[derive(Debug, Component)]
+pub struct EffectRegion<T> {
+    /// The shape of the region.
+    pub shape: RegionShape,
+    /// The units of the shape's positions and sizes and of the feather.
+    pub space: RegionSpace,
+    /// How far the effect fades out towards the edge of the shape, inwards.
+    /// Zero means a hard edge. Not used by [`RegionShape::Mask`].
+    pub feather: f32,
+    /// Apply the effect outside the region instead of inside.
+    pub invert: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> EffectRegion<T> {
+    /// Applies the effect inside the given shape, given in UV space.
+    pub fn new(shape: RegionShape) -> Self {
+        Self {
+            shape,
+            space: RegionSpace::default(),
+            feather: 0.0,
+            invert: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The shape is given in pixels instead.
+    pub fn in_pixels(mut self) -> Self {
+        self.space = RegionSpace::Pixels;
+        self
+    }
+
+    /// Fades the effect out over the given distance towards the shape's edge.
+    pub fn with_feather(mut self, feather: f32) -> Self {
+        self.feather = feather;
+        self
+    }
+
+    /// Applies the effect outside the shape instead of inside.
+    pub fn inverted(mut self) -> Self {
+        self.invert = true;
+        self
+    }
+}
+
+impl<T> Clone for EffectRegion<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shape: self.shape.clone(),
+            space: self.space,
+            feather: self.feather,
+            invert: self.invert,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static> ExtractComponent for EffectRegion<T> {
+    type QueryData = &'static Self;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(region: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        Some(region.clone())
+    }
+}
+
+/// The shape of an [`EffectRegion`].
+#[derive(Debug, Clone)]
+pub enum RegionShape {
+    /// An axis aligned rectangle.
+    Rect {
+        /// The top left corner.
+        min: Vec2,
+        /// The bottom right corner.
+        max: Vec2,
+    },
+    /// A circle.
+    ///
+    /// Note that in UV space this is stretched along with the screen.
+    Circle {
+        /// The center.
+        center: Vec2,
+        /// The radius.
+        radius: f32,
+    },
+    /// An axis aligned ellipse.
+    Ellipse {
+        /// The center.
+        center: Vec2,
+        /// The horizontal and vertical radii.
+        radii: Vec2,
+    },
+    /// A grayscale image stretched over the screen.
+    /// The red channel is how strongly the effect is applied.
+    Mask(Handle<Image>),
+}
+
+impl RegionShape {
+    /// The center and half size, or radii, of the shape.
+    fn center_and_extents(&self) -> (Vec2, Vec2) {
+        match *self {
+            RegionShape::Rect { min, max } => ((min + max) / 2.0, (max - min).abs() / 2.0),
+            RegionShape::Circle { center, radius } => (center, Vec2::splat(radius)),
+            RegionShape::Ellipse { center, radii } => (center, radii),
+            RegionShape::Mask(_) => (Vec2::ZERO, Vec2::ZERO),
+        }
+    }
+
+    pub(crate) fn shader_def(&self) -> &'static str {
+        match self {
+            RegionShape::Rect { .. } => "REGION_RECT",
+            RegionShape::Circle { .. } => "REGION_CIRCLE",
+            RegionShape::Ellipse { .. } => "REGION_ELLIPSE",
+            RegionShape::Mask(_) => "REGION_MASK",
+        }
+    }
+}
+
+/// The units of an [`EffectRegion`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RegionSpace {
+    /// From `(0, 0)` at the top left to `(1, 1)` at the bottom right of the screen.
+    #[default]
+    Uv,
+    /// Pixels of the camera's render target, from the top left.
+    Pixels,
+}
+
+/// The shader defs of the blend shader for the given [`RegionShape::shader_def`], if any.
+pub(crate) fn blend_shader_defs(region: Option<&'static str>) -> Vec<ShaderDefVal> {
+    match region {
+        Some(region) => vec!["REGION".into(), region.into()],
+        None => vec![],
+    }
+}
+
+/// The uniform of the blend shader.
+#[derive(Debug, Clone, Copy, ShaderType)]
+#[cfg_attr(test, derive(Reflect))]
+pub(crate) struct EffectBlendUniform {
+    weight: f32,
+    feather: f32,
+    invert: f32,
+    /// Converts UVs into the region's space.
+    space_scale: Vec2,
+    center: Vec2,
+    extents: Vec2,
+}
+
+/// The blend uniforms of all views using the effect `T`.
+#[derive(Resource)]
+struct EffectBlendUniforms<T> {
+    buffer: DynamicUniformBuffer<EffectBlendUniform>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for EffectBlendUniforms<T> {
+    fn default() -> Self {
+        Self {
+            buffer: DynamicUniformBuffer::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The pipeline blending an effect's input back over its output.
+///
+/// Specialized per view, see [`EffectBlendPipelineKey`].
+#[derive(Resource)]
+pub(crate) struct EffectBlendPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    shader: Handle<Shader>,
+}
+
+/// What the blend pipeline is specialized on.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct EffectBlendPipelineKey {
+    texture_format: TextureFormat,
+    /// The shader definition of the region's shape, if any.
+    region: Option<&'static str>,
+}
+
+impl FromWorld for EffectBlendPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "effect_blend_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    // The effect's input
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<EffectBlendUniform>(true),
+                    // The region's mask, a fallback image when there is none
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world
+            .resource::<AssetServer>()
+            .load(embedded_shader_path("blend.wgsl"));
+
+        Self {
+            layout,
+            sampler,
+            shader,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for EffectBlendPipeline {
+    type Key = EffectBlendPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let shader_defs = blend_shader_defs(key.region);
+
+        RenderPipelineDescriptor {
+            label: Some("effect_blend_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                shader_defs,
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: key.texture_format,
+                    // The shader outputs the input with an alpha of how much the effect is
+                    // left out, the alpha already in the target is kept.
+                    blend: Some(BlendState {
+                        color: BlendComponent {
+                            src_factor: BlendFactor::SrcAlpha,
+                            dst_factor: BlendFactor::OneMinusSrcAlpha,
+                            operation: BlendOperation::Add,
+                        },
+                        alpha: BlendComponent {
+                            src_factor: BlendFactor::Zero,
+                            dst_factor: BlendFactor::One,
+                            operation: BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+/// Blends the effect `T` with its input on a specific view.
+///
+/// Only present on views where the effect is partially applied.
+#[derive(Component)]
+pub struct ViewEffectBlend<T> {
+    pipeline: CachedRenderPipelineId,
+    offset: u32,
+    mask: Option<AssetId<Image>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> ViewEffectBlend<T> {
+    /// Whether the blend pipeline is compiled and the region's mask, if any, is loaded.
+    pub(crate) fn is_ready(&self, world: &World) -> bool {
+        let pipeline_ready = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(self.pipeline)
+            .is_some();
+        let mask_ready = self.mask.is_none_or(|mask| {
+            world
+                .resource::<RenderAssets<GpuImage>>()
+                .get(mask)
+                .is_some()
+        });
+
+        pipeline_ready && mask_ready
+    }
+
+    /// Blends `source`, the effect's input, over `destination`, the effect's output.
+    pub(crate) fn render(
+        &self,
+        render_context: &mut RenderContext,
+        world: &World,
+        source: &TextureView,
+        destination: &TextureView,
+    ) {
+        let blend_pipeline = world.resource::<EffectBlendPipeline>();
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(self.pipeline)
+        else {
+            return;
+        };
+        let Some(uniforms) = world.resource::<EffectBlendUniforms<T>>().buffer.binding() else {
+            return;
+        };
+        let mask = match self.mask {
+            Some(mask) => match world.resource::<RenderAssets<GpuImage>>().get(mask) {
+                Some(mask) => mask,
+                None => return,
+            },
+            None => &world.resource::<FallbackImage>().d2,
+        };
+
+        let bind_group = render_context.render_device().create_bind_group(
+            "effect_blend_bind_group",
+            &blend_pipeline.layout,
+            &BindGroupEntries::sequential((
+                source,
+                &blend_pipeline.sampler,
+                uniforms,
+                &mask.texture_view,
+                &mask.sampler,
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("effect_blend_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: destination,
+                resolve_target: None,
+                // Keep the effect's output, it is blended with the input.
+                ops: Operations {
+                    load: LoadOp::Load,
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[self.offset]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// Whether an effect is left out entirely on a view because of its weight.
+pub(crate) fn is_weightless<T>(weight: Option<&EffectWeight<T>>) -> bool {
+    weight.is_some_and(|weight| weight.weight <= 0.0)
+}
+
+/// The components of a view which make the effect `T` partially applied.
+type ViewBlendQuery<T> = AnyOf<(&'static EffectWeight<T>, &'static EffectRegion<T>)>;
+
+fn prepare_effect_blend<T: 'static>(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    pipeline_cache: Res<PipelineCache>,
+    blend_pipeline: Res<EffectBlendPipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<EffectBlendPipeline>>,
+    mut uniforms: ResMut<EffectBlendUniforms<T>>,
+    views: Query<(Entity, &ViewTarget, ViewBlendQuery<T>)>,
+) {
+    uniforms.buffer.clear();
+
+    for (entity, view_target, (weight, region)) in &views {
+        let weight = weight.map_or(1.0, |weight| weight.weight);
+
+        // At full weight and without a region the effect's output is used as is.
+        if weight >= 1.0 && region.is_none() {
+            commands.entity(entity).remove::<ViewEffectBlend<T>>();
+            continue;
+        }
+
+        let mut uniform = EffectBlendUniform {
+            weight,
+            feather: 0.0,
+            invert: 0.0,
+            space_scale: Vec2::ONE,
+            center: Vec2::ZERO,
+            extents: Vec2::ZERO,
+        };
+        if let Some(region) = region {
+            let (center, extents) = region.shape.center_and_extents();
+            let size = view_target.main_texture().size();
+
+            uniform.feather = region.feather;
+            uniform.invert = if region.invert { 1.0 } else { 0.0 };
+            uniform.space_scale = match region.space {
+                RegionSpace::Uv => Vec2::ONE,
+                RegionSpace::Pixels => Vec2::new(size.width as f32, size.height as f32),
+            };
+            uniform.center = center;
+            uniform.extents = extents;
+        }
+
+        let offset = uniforms.buffer.push(&uniform);
+        let pipeline = pipelines.specialize(
+            &pipeline_cache,
+            &blend_pipeline,
+            EffectBlendPipelineKey {
+                texture_format: view_target.main_texture_format(),
+                region: region.map(|region| region.shape.shader_def()),
+            },
+        );
+        let mask = region.and_then(|region| match &region.shape {
+            RegionShape::Mask(mask) => Some(mask.id()),
+            _ => None,
+        });
+
+        commands.entity(entity).insert(ViewEffectBlend::<T> {
+            pipeline,
+            offset,
+            mask,
+            _marker: PhantomData,
+        });
+    }
+
+    uniforms.buffer.write_buffer(&render_device, &render_queue);
+}
+
+/// Adds [`EffectWeight`] and [`EffectRegion`] support for the effect `T`.
+///
+/// Added by the effect plugins, so it does not need to be added manually.
+pub(crate) struct EffectBlendPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for EffectBlendPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: 'static> Plugin for EffectBlendPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<EffectWeight<T>>::default(),
+            ExtractComponentPlugin::<EffectRegion<T>>::default(),
+        ));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<EffectBlendUniforms<T>>()
+            .init_resource::<SpecializedRenderPipelines<EffectBlendPipeline>>()
+            .add_systems(
+                Render,
+                prepare_effect_blend::<T>.in_set(RenderSet::PrepareResources),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<EffectBlendPipeline>();
+    }
+}
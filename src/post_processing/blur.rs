@@ -5,14 +5,24 @@ use bevy::{
 
 use std::fmt::Display;
 
-use super::simple_post_process::SimplePostProcess;
+use super::{
+    impulse::ImpulseEffect, shader::embedded_shader_path, simple_post_process::SimplePostProcess,
+    tween::EffectLerp,
+};
 
 ///TODO
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
 pub struct BlurPostProcessLabel;
 
 /// Pixelate settings.
-#[derive(Component, Clone, Copy, ExtractComponent, ShaderType)]
+#[derive(Component, Clone, Copy, ExtractComponent, ShaderType, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub struct Blur {
     /// How blurry the output image should be.
     /// If `0.0`, no blur is applied.
@@ -34,6 +44,24 @@ impl Default for Blur {
     }
 }
 
+impl EffectLerp for Blur {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            amount: self.amount.lerp(other.amount, t),
+            kernel_radius: self.kernel_radius.lerp(other.kernel_radius, t),
+        }
+    }
+}
+
+impl ImpulseEffect for Blur {
+    fn at_rest(&self) -> Self {
+        Self {
+            amount: 0.0,
+            ..*self
+        }
+    }
+}
+
 impl Display for Blur {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -46,7 +74,7 @@ impl Display for Blur {
 
 impl SimplePostProcess for Blur {
     fn shader_path() -> String {
-        concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/", "blur.wgsl").into()
+        embedded_shader_path("blur.wgsl")
     }
     type Label = BlurPostProcessLabel;
 }
@@ -5,14 +5,24 @@ use bevy::{
 
 use std::{f32::consts::PI, fmt::Display};
 
-use super::simple_post_process::SimplePostProcess;
+use super::{
+    impulse::ImpulseEffect, shader::embedded_shader_path, simple_post_process::SimplePostProcess,
+    tween::EffectLerp,
+};
 
 ///TODO
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
 pub struct ChromaticAberrationPostProcessLabel;
 
 /// Pixelate settings.
-#[derive(Component, Clone, Copy, ExtractComponent, ShaderType)]
+#[derive(Component, Clone, Copy, ExtractComponent, ShaderType, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub struct ChromaticAberration {
     /// The direction (in UV space) the red channel is offset in.
     /// Will be normalized.
@@ -60,6 +70,30 @@ impl Default for ChromaticAberration {
     }
 }
 
+impl EffectLerp for ChromaticAberration {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            dir_r: self.dir_r.lerp(other.dir_r, t),
+            magnitude_r: self.magnitude_r.lerp(other.magnitude_r, t),
+            dir_g: self.dir_g.lerp(other.dir_g, t),
+            magnitude_g: self.magnitude_g.lerp(other.magnitude_g, t),
+            dir_b: self.dir_b.lerp(other.dir_b, t),
+            magnitude_b: self.magnitude_b.lerp(other.magnitude_b, t),
+        }
+    }
+}
+
+impl ImpulseEffect for ChromaticAberration {
+    fn at_rest(&self) -> Self {
+        Self {
+            magnitude_r: 0.0,
+            magnitude_g: 0.0,
+            magnitude_b: 0.0,
+            ..*self
+        }
+    }
+}
+
 impl Display for ChromaticAberration {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let base_angle = Vec2::new(1., 0.);
@@ -80,12 +114,7 @@ impl Display for ChromaticAberration {
 
 impl SimplePostProcess for ChromaticAberration {
     fn shader_path() -> String {
-        concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/assets/shaders/",
-            "chromatic-aberration.wgsl"
-        )
-        .into()
+        embedded_shader_path("chromatic-aberration.wgsl")
     }
     type Label = ChromaticAberrationPostProcessLabel;
 }
@@ -0,0 +1,215 @@
+use std::num::NonZeroU64;
+
+use bevy::{
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        globals::GlobalsBuffer,
+        render_resource::{BindingResource, BufferBinding, ShaderType},
+    },
+    time::TimeSystem,
+};
+
+/// How long the clock runs before its time wraps to zero, like Bevy's `globals.time`.
+const WRAP_PERIOD: f64 = 3600.0;
+
+/// The time effects on a camera see, in place of Bevy's `globals.time`.
+///
+/// Without this component effects animate with real time. With it they can be paused
+/// with the game, slowed down or sped up, and rewound or replayed deterministically:
+///
+/// ```rust,ignore
+/// commands.spawn((
+///     Camera3d::default(),
+///     Wave::default(),
+///     EffectClock::virtual_time(),
+/// ));
+///
+/// // Bullet time.
+/// clock.scale = 0.2;
+/// ```
+///
+/// Effect shaders read it as the `globals` binding of group 0, so existing shaders work unchanged.
+#[derive(Debug, Clone, Component, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub struct EffectClock {
+    /// How fast the clock runs relative to its [`ClockSource`]. Negative values run it backwards.
+    pub scale: f32,
+    /// Whether the clock is stopped.
+    pub paused: bool,
+    /// Seconds added to the elapsed time.
+    pub offset: f32,
+    /// The time the clock follows.
+    pub source: ClockSource,
+    elapsed: f64,
+    delta: f32,
+    frame_count: u32,
+}
+
+impl EffectClock {
+    /// A clock following real time.
+    pub fn new() -> Self {
+        Self {
+            scale: 1.0,
+            paused: false,
+            offset: 0.0,
+            source: ClockSource::default(),
+            elapsed: 0.0,
+            delta: 0.0,
+            frame_count: 0,
+        }
+    }
+
+    /// A clock following [`Time<Virtual>`], so it pauses and scales with the game.
+    pub fn virtual_time() -> Self {
+        Self {
+            source: ClockSource::Virtual,
+            ..Self::new()
+        }
+    }
+
+    /// Runs the clock at the given scale.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Stops the clock.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Restarts a stopped clock.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// The seconds the clock ran, ignoring [`Self::offset`].
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Sets the seconds the clock ran, e.g. to replay a recording.
+    pub fn set_elapsed(&mut self, elapsed: f64) {
+        self.elapsed = elapsed;
+    }
+
+    /// The time effects see, in seconds.
+    pub fn time(&self) -> f32 {
+        (self.elapsed + self.offset as f64).rem_euclid(WRAP_PERIOD) as f32
+    }
+
+    /// Advances the clock by the given seconds of its source's time.
+    pub fn tick(&mut self, delta: f32) {
+        if self.paused {
+            self.delta = 0.0;
+            return;
+        }
+
+        self.delta = delta * self.scale;
+        self.elapsed += self.delta as f64;
+        self.frame_count = self.frame_count.wrapping_add(1);
+    }
+}
+
+impl Default for EffectClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The time an [`EffectClock`] follows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub enum ClockSource {
+    /// [`Time<Real>`], which keeps running when the game is paused.
+    #[default]
+    Real,
+    /// [`Time<Virtual>`], which pauses and scales with the game.
+    Virtual,
+}
+
+/// An [`EffectClock`] as effect shaders see it.
+///
+/// This has the layout of Bevy's `Globals` shader struct.
+#[derive(Debug, Clone, Copy, Component, ShaderType)]
+#[cfg_attr(test, derive(Reflect))]
+pub struct EffectClockUniform {
+    time: f32,
+    delta_time: f32,
+    frame_count: u32,
+    // WebGL2 requires uniforms to be 16 byte aligned.
+    _padding: f32,
+}
+
+impl ExtractComponent for EffectClock {
+    type QueryData = &'static Self;
+    type QueryFilter = With<Camera>;
+    type Out = EffectClockUniform;
+
+    fn extract_component(clock: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        Some(EffectClockUniform {
+            time: clock.time(),
+            delta_time: clock.delta,
+            frame_count: clock.frame_count,
+            _padding: 0.0,
+        })
+    }
+}
+
+fn tick_effect_clocks(
+    real: Res<Time<Real>>,
+    virtual_time: Res<Time<Virtual>>,
+    mut clocks: Query<&mut EffectClock>,
+) {
+    for mut clock in &mut clocks {
+        let delta = match clock.source {
+            ClockSource::Real => real.delta_secs(),
+            ClockSource::Virtual => virtual_time.delta_secs(),
+        };
+        clock.tick(delta);
+    }
+}
+
+/// The `globals` binding effects on the given view see: its [`EffectClock`] if it has one,
+/// Bevy's globals otherwise.
+pub(crate) fn globals_binding(world: &World, view: Entity) -> Option<BindingResource<'_>> {
+    let Some(index) = world.get::<DynamicUniformIndex<EffectClockUniform>>(view) else {
+        return world.resource::<GlobalsBuffer>().buffer.binding();
+    };
+
+    let buffer = world
+        .get_resource::<ComponentUniforms<EffectClockUniform>>()?
+        .uniforms()
+        .buffer()?;
+
+    // The shared layouts bind a single `Globals` without a dynamic offset,
+    // so the view's clock is bound as a range of the buffer instead.
+    Some(BindingResource::Buffer(BufferBinding {
+        buffer,
+        offset: index.index() as u64,
+        size: NonZeroU64::new(EffectClockUniform::min_size().get()),
+    }))
+}
+
+/// Adds [`EffectClock`] support.
+pub(crate) struct EffectClockPlugin;
+
+impl Plugin for EffectClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<EffectClock>::default(),
+            UniformComponentPlugin::<EffectClockUniform>::default(),
+        ))
+        .register_type::<EffectClock>()
+        .add_systems(First, tick_effect_clocks.after(TimeSystem));
+    }
+}
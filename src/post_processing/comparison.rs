@@ -0,0 +1,442 @@
+use bevy::{
+    core_pipeline::{
+        blit::{BlitPipeline, BlitPipelineKey},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_graph::{InternedRenderLabel, RenderLabel},
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice, RenderQueue},
+        texture::{CachedTexture, TextureCache},
+        view::ViewTarget,
+        Render, RenderApp, RenderSet,
+    },
+};
+
+use super::shader::embedded_shader_path;
+
+/// Shows the image from before effects on one side of a divider line on a camera,
+/// to compare it with the image after them.
+///
+/// Either the whole effect stack or a single effect, identified by its render label, is compared.
+/// The divider may be moved and rotated at runtime, e.g. to follow the cursor.
+///
+/// ```rust,ignore
+/// // The image without any effects on the left half of the screen.
+/// commands.spawn((
+///     Camera3d::default(),
+///     Lut::default(),
+///     Wave::default(),
+///     EffectComparison::stack(),
+/// ));
+///
+/// // The image without the LUT on the top left, divided diagonally.
+/// commands.spawn((
+///     Camera3d::default(),
+///     Lut::default(),
+///     Wave::default(),
+///     EffectComparison::effect(LutPostProcessLabel).with_angle(FRAC_PI_4),
+/// ));
+/// ```
+#[derive(Debug, Clone, Component, ExtractComponent)]
+pub struct EffectComparison {
+    /// What the image is compared with.
+    pub target: ComparisonTarget,
+    /// A point on the divider, from `(0, 0)` at the top left to `(1, 1)` at the bottom right.
+    pub position: Vec2,
+    /// The angle of the divider in radians, clockwise.
+    /// At zero the divider is vertical with the image before on its left.
+    pub angle: f32,
+    /// The width of the divider line in pixels. Zero hides it.
+    pub line_width: f32,
+    /// The color of the divider line.
+    pub line_color: Color,
+}
+
+impl EffectComparison {
+    /// Compares the image before and after all effects on the camera.
+    pub fn stack() -> Self {
+        Self {
+            target: ComparisonTarget::Stack,
+            position: Vec2::splat(0.5),
+            angle: 0.0,
+            line_width: 2.0,
+            line_color: Color::WHITE,
+        }
+    }
+
+    /// Compares the image before and after the effect with the given label.
+    ///
+    /// The effect is never fused with others, see [`super::fusion::PostProcessFusion`].
+    pub fn effect(label: impl RenderLabel) -> Self {
+        Self {
+            target: ComparisonTarget::Effect(label.intern()),
+            ..Self::stack()
+        }
+    }
+
+    /// Moves the divider through the given point, in UV space.
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Rotates the divider clockwise by the given angle in radians.
+    pub fn with_angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    /// Draws the divider line with the given width in pixels and color.
+    pub fn with_line(mut self, width: f32, color: impl Into<Color>) -> Self {
+        self.line_width = width;
+        self.line_color = color.into();
+        self
+    }
+
+    /// Hides the divider line.
+    pub fn without_line(mut self) -> Self {
+        self.line_width = 0.0;
+        self
+    }
+}
+
+impl Default for EffectComparison {
+    fn default() -> Self {
+        Self::stack()
+    }
+}
+
+/// What an [`EffectComparison`] compares.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonTarget {
+    /// All effects on the camera.
+    #[default]
+    Stack,
+    /// The effect with the given render label, e.g. [`super::lut::LutPostProcessLabel`].
+    Effect(InternedRenderLabel),
+}
+
+/// The uniform of the comparison shader.
+#[derive(Debug, Clone, Copy, ShaderType)]
+#[cfg_attr(test, derive(Reflect))]
+pub(crate) struct EffectComparisonUniform {
+    line_color: Vec4,
+    size: Vec2,
+    position: Vec2,
+    normal: Vec2,
+    line_width: f32,
+}
+
+/// The comparison uniforms of all views.
+#[derive(Resource, Default)]
+struct EffectComparisonUniforms {
+    buffer: DynamicUniformBuffer<EffectComparisonUniform>,
+}
+
+/// The pipeline drawing the image before effects over the image after them.
+#[derive(Resource)]
+pub(crate) struct EffectComparisonPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    shader: Handle<Shader>,
+}
+
+impl FromWorld for EffectComparisonPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "effect_comparison_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    // The image before the effects
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<EffectComparisonUniform>(true),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world
+            .resource::<AssetServer>()
+            .load(embedded_shader_path("compare.wgsl"));
+
+        Self {
+            layout,
+            sampler,
+            shader,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for EffectComparisonPipeline {
+    type Key = TextureFormat;
+
+    fn specialize(&self, texture_format: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("effect_comparison_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: texture_format,
+                    // As for partially applied effects, see `EffectBlendPipeline`.
+                    blend: Some(BlendState {
+                        color: BlendComponent {
+                            src_factor: BlendFactor::SrcAlpha,
+                            dst_factor: BlendFactor::OneMinusSrcAlpha,
+                            operation: BlendOperation::Add,
+                        },
+                        alpha: BlendComponent {
+                            src_factor: BlendFactor::Zero,
+                            dst_factor: BlendFactor::One,
+                            operation: BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+/// The pipelines and the stored image of an [`EffectComparison`] on a specific view.
+#[derive(Component)]
+pub(crate) struct ViewEffectComparison {
+    target: ComparisonTarget,
+    pipeline: CachedRenderPipelineId,
+    blit_pipeline: CachedRenderPipelineId,
+    /// The image before the compared effects.
+    before: CachedTexture,
+    offset: u32,
+}
+
+impl ViewEffectComparison {
+    /// Whether the whole effect stack is compared.
+    pub(crate) fn compares_stack(&self) -> bool {
+        self.target == ComparisonTarget::Stack
+    }
+
+    /// Whether the effect with the given label is compared.
+    pub(crate) fn compares(&self, label: InternedRenderLabel) -> bool {
+        self.target == ComparisonTarget::Effect(label)
+    }
+
+    /// Whether the pipelines are compiled.
+    pub(crate) fn is_ready(&self, world: &World) -> bool {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        pipeline_cache.get_render_pipeline(self.pipeline).is_some()
+            && pipeline_cache
+                .get_render_pipeline(self.blit_pipeline)
+                .is_some()
+    }
+
+    /// Stores the view's current image, from before the compared effects.
+    pub(crate) fn store(
+        &self,
+        render_context: &mut RenderContext,
+        world: &World,
+        view_target: &ViewTarget,
+    ) {
+        let blit = world.resource::<BlitPipeline>();
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(self.blit_pipeline)
+        else {
+            return;
+        };
+
+        let bind_group = render_context.render_device().create_bind_group(
+            "effect_comparison_blit_bind_group",
+            &blit.texture_bind_group,
+            &BindGroupEntries::sequential((view_target.main_texture_view(), &blit.sampler)),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("effect_comparison_store_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: &self.before.default_view,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// Draws the stored image over the view's current image, from after the compared effects,
+    /// on one side of the divider.
+    pub(crate) fn render(
+        &self,
+        render_context: &mut RenderContext,
+        world: &World,
+        view_target: &ViewTarget,
+    ) {
+        let comparison_pipeline = world.resource::<EffectComparisonPipeline>();
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(self.pipeline)
+        else {
+            return;
+        };
+        let Some(uniforms) = world
+            .resource::<EffectComparisonUniforms>()
+            .buffer
+            .binding()
+        else {
+            return;
+        };
+
+        let bind_group = render_context.render_device().create_bind_group(
+            "effect_comparison_bind_group",
+            &comparison_pipeline.layout,
+            &BindGroupEntries::sequential((
+                &self.before.default_view,
+                &comparison_pipeline.sampler,
+                uniforms,
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("effect_comparison_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: view_target.main_texture_view(),
+                resolve_target: None,
+                // Keep the image after the effects, the image before is drawn over it.
+                ops: Operations {
+                    load: LoadOp::Load,
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[self.offset]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+fn prepare_effect_comparison(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut texture_cache: ResMut<TextureCache>,
+    pipeline_cache: Res<PipelineCache>,
+    comparison_pipeline: Res<EffectComparisonPipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<EffectComparisonPipeline>>,
+    mut blit_pipelines: ResMut<SpecializedRenderPipelines<BlitPipeline>>,
+    blit_pipeline: Res<BlitPipeline>,
+    mut uniforms: ResMut<EffectComparisonUniforms>,
+    views: Query<(Entity, &ViewTarget, &EffectComparison)>,
+    stale: Query<Entity, (With<ViewEffectComparison>, Without<EffectComparison>)>,
+) {
+    for entity in &stale {
+        commands.entity(entity).remove::<ViewEffectComparison>();
+    }
+
+    uniforms.buffer.clear();
+
+    for (entity, view_target, comparison) in &views {
+        let size = view_target.main_texture().size();
+        let texture_format = view_target.main_texture_format();
+
+        let before = texture_cache.get(
+            &render_device,
+            TextureDescriptor {
+                label: Some("effect_comparison_texture"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: texture_format,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+
+        let size = Vec2::new(size.width as f32, size.height as f32);
+        let offset = uniforms.buffer.push(&EffectComparisonUniform {
+            line_color: comparison.line_color.to_linear().to_vec4(),
+            size,
+            position: comparison.position * size,
+            normal: Vec2::from_angle(comparison.angle),
+            line_width: comparison.line_width,
+        });
+
+        let pipeline = pipelines.specialize(&pipeline_cache, &comparison_pipeline, texture_format);
+        let blit_pipeline = blit_pipelines.specialize(
+            &pipeline_cache,
+            &blit_pipeline,
+            BlitPipelineKey {
+                texture_format,
+                blend_state: None,
+                samples: 1,
+            },
+        );
+
+        commands.entity(entity).insert(ViewEffectComparison {
+            target: comparison.target,
+            pipeline,
+            blit_pipeline,
+            before,
+            offset,
+        });
+    }
+
+    uniforms.buffer.write_buffer(&render_device, &render_queue);
+}
+
+/// Adds [`EffectComparison`] support to the effect stack.
+///
+/// Added by the effect plugins, so it does not need to be added manually.
+#[derive(Debug, Default)]
+pub(crate) struct EffectComparisonPlugin;
+
+impl Plugin for EffectComparisonPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ExtractComponentPlugin::<EffectComparison>::default());
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<EffectComparisonUniforms>()
+            .init_resource::<SpecializedRenderPipelines<EffectComparisonPipeline>>()
+            .add_systems(
+                Render,
+                prepare_effect_comparison.in_set(RenderSet::PrepareResources),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<EffectComparisonPipeline>();
+    }
+}
@@ -0,0 +1,524 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    core_pipeline::blit::{BlitPipeline, BlitPipelineKey},
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{
+            DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin, UniformComponentPlugin,
+        },
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        globals::GlobalsUniform,
+        render_graph::{NodeRunError, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner},
+        render_resource::{
+            binding_types::{sampler, texture_2d, texture_storage_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::{CachedTexture, TextureCache},
+        view::ViewTarget,
+        Render, RenderApp, RenderSet,
+    },
+};
+use encase::internal::WriteInto;
+
+use super::{
+    blend::{is_weightless, EffectBlendPlugin, EffectWeight, ViewEffectBlend},
+    clock::globals_binding,
+    diagnostics::effect_name,
+    shader::{init_effect_shader, EffectShader},
+    simple_post_process::{EffectBindGroup, TextureInputs},
+    skipped::{report_skipped, SkipReason},
+    stack::register_effect_node,
+    view_bindings::{add_view_bindings, ViewBindings, ViewBindingsKey, ViewBindingsLayouts},
+};
+
+/// The format of the storage texture compute effects write to.
+pub const COMPUTE_OUTPUT_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+/// A post processing effect running as a compute shader.
+///
+/// This mirrors [`super::simple_post_process::SimplePostProcess`], but the shader's
+/// `compute` entry point is dispatched once per pixel of the view instead of drawing
+/// a fullscreen triangle. This allows effects to share work between pixels via
+/// workgroup memory.
+///
+/// Group 0 holds the effect's input, a sampler, the globals and the output, a
+/// `texture_storage_2d<rgba16float, write>` the size of the view. The output is copied
+/// into the view afterwards. Group 1 holds the effect's uniform and textures, and group 2
+/// the view if [`Self::view_bindings`].
+///
+/// The shader defs `WORKGROUP_SIZE_X` and `WORKGROUP_SIZE_Y` hold [`Self::workgroup_size`],
+/// for use as `@workgroup_size(#{WORKGROUP_SIZE_X}, #{WORKGROUP_SIZE_Y}, 1)`.
+pub trait ComputePostProcess:
+    Component + Clone + Default + ExtractComponent<Out: Component + Clone + WriteInto + ShaderType>
+{
+    /// The label used to identify the post-processing effect.
+    type Label: RenderLabel + Default;
+    /// The shader path used for the post-processing effect.
+    fn shader_path() -> String;
+    /// The size of the shader's workgroups, in pixels.
+    fn workgroup_size() -> UVec2 {
+        UVec2::splat(8)
+    }
+    /// The bind group layout used to pass data to the shader.
+    fn layout(device: &RenderDevice) -> BindGroupLayout {
+        device.create_bind_group_layout(
+            None,
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (uniform_buffer::<Self::Out>(true),),
+            ),
+        )
+    }
+    /// The bind group used to pass data to the shader.
+    ///
+    /// It is shared by all views and only recreated when the uniform buffer or the textures change.
+    /// Returning `None`, e.g. while a texture is loading, skips the effect until then.
+    fn bind_group(
+        _world: &World,
+        device: &RenderDevice,
+        layout: &BindGroupLayout,
+        buffer: BindingResource,
+        _textures: &TextureInputs,
+    ) -> Option<BindGroup> {
+        Some(device.create_bind_group(None, layout, &BindGroupEntries::sequential((buffer,))))
+    }
+    /// The textures passed to [`Self::bind_group`].
+    fn textures(_world: &mut World) -> TextureInputs {
+        TextureInputs::None
+    }
+    /// The shader definitions used for the post-processing effect.
+    ///
+    /// These are read per camera, so cameras may use different shader variants of the same effect.
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        vec![]
+    }
+    /// Whether the effect reads the view's uniform and prepass textures, bound at group 2.
+    ///
+    /// See [`ViewBindingsKey`] for how shaders access them.
+    fn view_bindings() -> bool {
+        false
+    }
+}
+
+/// The compute pipeline layouts of an effect.
+///
+/// The actual pipelines are specialized per view, see [`ComputePostProcessPipelineKey`].
+#[derive(Resource)]
+pub struct ComputePostProcessPipeline<T: ComputePostProcess> {
+    layouts: Vec<BindGroupLayout>,
+    view_layouts: Option<ViewBindingsLayouts>,
+    sampler: Sampler,
+    _marker: PhantomData<T>,
+}
+
+impl<T: ComputePostProcess> FromWorld for ComputePostProcessPipeline<T> {
+    fn from_world(world: &mut World) -> Self {
+        let view_layouts =
+            T::view_bindings().then(|| world.get_resource_or_init::<ViewBindingsLayouts>().clone());
+        let render_device = world.resource::<RenderDevice>();
+
+        let shared_layout = render_device.create_bind_group_layout(
+            "compute_post_process_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    // The screen texture
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<GlobalsUniform>(false),
+                    // The effect's output
+                    texture_storage_2d(COMPUTE_OUTPUT_FORMAT, StorageTextureAccess::WriteOnly),
+                ),
+            ),
+        );
+
+        Self {
+            layouts: vec![shared_layout, T::layout(render_device)],
+            view_layouts,
+            sampler: render_device.create_sampler(&SamplerDescriptor::default()),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// What a compute post processing pipeline is specialized on.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ComputePostProcessPipelineKey {
+    /// The effect's shader, see [`EffectShader`].
+    pub shader: Handle<Shader>,
+    /// The shader definitions used when compiling the shader.
+    pub shader_defs: Vec<ShaderDefVal>,
+    /// The view's prepass textures, if the effect reads the view.
+    pub view_bindings: Option<ViewBindingsKey>,
+}
+
+/// The shader defs telling a compute shader its workgroup size, see [`ComputePostProcess::workgroup_size`].
+pub(crate) fn workgroup_size_shader_defs(workgroup_size: UVec2) -> [ShaderDefVal; 2] {
+    [
+        ShaderDefVal::UInt("WORKGROUP_SIZE_X".into(), workgroup_size.x),
+        ShaderDefVal::UInt("WORKGROUP_SIZE_Y".into(), workgroup_size.y),
+    ]
+}
+
+impl<T: ComputePostProcess> SpecializedComputePipeline for ComputePostProcessPipeline<T> {
+    type Key = ComputePostProcessPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> ComputePipelineDescriptor {
+        let workgroup_size = T::workgroup_size();
+        let mut layouts = self.layouts.clone();
+        let mut shader_defs = key.shader_defs;
+        if let (Some(view_layouts), Some(view_key)) = (&self.view_layouts, key.view_bindings) {
+            view_layouts.specialize(view_key, &mut layouts, &mut shader_defs);
+        }
+        shader_defs.extend(workgroup_size_shader_defs(workgroup_size));
+
+        ComputePipelineDescriptor {
+            label: Some(effect_name(T::Label::default()).into()),
+            layout: layouts,
+            push_constant_ranges: vec![],
+            shader: key.shader,
+            shader_defs,
+            entry_point: "compute".into(),
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+/// The textures of a compute effect, see [`ComputePostProcess::textures`].
+#[derive(Resource, ExtractResource, Clone)]
+pub struct ComputePostProcessImage<T: ComputePostProcess> {
+    /// The textures passed to [`ComputePostProcess::bind_group`].
+    pub texture_inputs: TextureInputs,
+    _marker: PhantomData<T>,
+}
+
+impl<T: ComputePostProcess> FromWorld for ComputePostProcessImage<T> {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            texture_inputs: T::textures(world),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The shader definitions a compute effect uses on a specific view.
+#[derive(Component)]
+pub struct ViewComputeShaderDefs<T> {
+    /// See [`ComputePostProcess::shader_defs`].
+    pub shader_defs: Vec<ShaderDefVal>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: ComputePostProcess> ExtractComponent for ViewComputeShaderDefs<T> {
+    type QueryData = &'static T;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(effect: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        Some(Self {
+            shader_defs: effect.shader_defs(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// The pipelines and output texture of a compute effect on a specific view.
+#[derive(Component)]
+pub struct ViewComputePostProcess<T> {
+    pipeline: CachedComputePipelineId,
+    blit_pipeline: CachedRenderPipelineId,
+    output: CachedTexture,
+    _marker: PhantomData<fn() -> T>,
+}
+
+fn prepare_compute_post_process<T: ComputePostProcess>(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    mut texture_cache: ResMut<TextureCache>,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedComputePipelines<ComputePostProcessPipeline<T>>>,
+    compute_pipeline: Res<ComputePostProcessPipeline<T>>,
+    mut blit_pipelines: ResMut<SpecializedRenderPipelines<BlitPipeline>>,
+    blit_pipeline: Res<BlitPipeline>,
+    shader: Res<EffectShader<T>>,
+    views: Query<(
+        Entity,
+        &ViewTarget,
+        &ViewComputeShaderDefs<T>,
+        Option<&ViewBindingsKey>,
+    )>,
+) {
+    for (entity, view_target, shader_defs, view_bindings) in &views {
+        let output = texture_cache.get(
+            &render_device,
+            TextureDescriptor {
+                label: Some("compute_post_process_output_texture"),
+                size: view_target.main_texture().size(),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: COMPUTE_OUTPUT_FORMAT,
+                usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+
+        let pipeline = pipelines.specialize(
+            &pipeline_cache,
+            &compute_pipeline,
+            ComputePostProcessPipelineKey {
+                shader: shader.handle.clone(),
+                shader_defs: shader_defs.shader_defs.clone(),
+                view_bindings: T::view_bindings()
+                    .then(|| view_bindings.copied().unwrap_or_default()),
+            },
+        );
+        let blit_pipeline = blit_pipelines.specialize(
+            &pipeline_cache,
+            &blit_pipeline,
+            BlitPipelineKey {
+                texture_format: view_target.main_texture_format(),
+                blend_state: None,
+                samples: 1,
+            },
+        );
+
+        commands.entity(entity).insert(ViewComputePostProcess::<T> {
+            pipeline,
+            blit_pipeline,
+            output,
+            _marker: PhantomData,
+        });
+    }
+}
+
+fn prepare_compute_post_process_bind_group<T: ComputePostProcess>(world: &mut World) {
+    world.resource_scope(|world, mut cached: Mut<EffectBindGroup<T>>| {
+        cached.prepare::<T::Out>(
+            world,
+            &world.resource::<ComputePostProcessPipeline<T>>().layouts[1],
+            &world
+                .resource::<ComputePostProcessImage<T>>()
+                .texture_inputs,
+            T::bind_group,
+        );
+    });
+}
+
+/// Dispatches a compute effect over a view, then copies its output into the view.
+#[derive(Default)]
+pub struct ComputePostProcessNode<T: ComputePostProcess>(PhantomData<fn() -> T>);
+
+impl<T: ComputePostProcess> ViewNode for ComputePostProcessNode<T> {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static DynamicUniformIndex<T::Out>,
+        &'static ViewComputePostProcess<T>,
+        Option<&'static EffectWeight<T>>,
+        Option<&'static ViewEffectBlend<T>>,
+        Option<&'static ViewBindings>,
+    );
+
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, settings_index, view_compute, weight, blend, view_bindings): QueryItem<
+            Self::ViewQuery,
+        >,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        if is_weightless(weight) {
+            return Ok(());
+        }
+
+        let compute_pipeline = world.resource::<ComputePostProcessPipeline<T>>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        let (Some(pipeline), Some(blit_pipeline)) = (
+            pipeline_cache.get_compute_pipeline(view_compute.pipeline),
+            pipeline_cache.get_render_pipeline(view_compute.blit_pipeline),
+        ) else {
+            return Ok(());
+        };
+
+        // A partially applied effect is only shown once it can be blended with its input.
+        if blend.is_some_and(|blend| !blend.is_ready(world)) {
+            return Ok(());
+        }
+
+        // Effects reading the view wait for its prepass textures.
+        if T::view_bindings() && view_bindings.is_none() {
+            return Ok(());
+        }
+
+        let Some(bind_group) = &world.resource::<EffectBindGroup<T>>().bind_group else {
+            report_skipped::<T>(
+                world,
+                graph.view_entity(),
+                T::Label::default(),
+                SkipReason::BindGroupUnavailable,
+            );
+            return Ok(());
+        };
+
+        let Some(globals) = globals_binding(world, graph.view_entity()) else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+
+        // The shared bind group holds the ping-pong source, so it is created each frame.
+        let shared_bind_group = render_context.render_device().create_bind_group(
+            "compute_post_process_bind_group",
+            &compute_pipeline.layouts[0],
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &compute_pipeline.sampler,
+                globals,
+                &view_compute.output.default_view,
+            )),
+        );
+
+        let size = view_compute.output.texture.size();
+        let workgroup_size = T::workgroup_size().max(UVec2::ONE);
+
+        let label = effect_name(T::Label::default());
+        let mut compute_pass =
+            render_context
+                .command_encoder()
+                .begin_compute_pass(&ComputePassDescriptor {
+                    label: Some(&label),
+                    timestamp_writes: None,
+                });
+
+        compute_pass.set_pipeline(pipeline);
+        compute_pass.set_bind_group(0, &shared_bind_group, &[]);
+        compute_pass.set_bind_group(1, bind_group, &[settings_index.index()]);
+        if let Some(view_bindings) = view_bindings.filter(|_| T::view_bindings()) {
+            compute_pass.set_bind_group(2, view_bindings.bind_group(), &[view_bindings.offset()]);
+        }
+        compute_pass.dispatch_workgroups(
+            size.width.div_ceil(workgroup_size.x),
+            size.height.div_ceil(workgroup_size.y),
+            1,
+        );
+        drop(compute_pass);
+
+        // Storage textures can't have the view's format, so the output is copied over.
+        let blit = world.resource::<BlitPipeline>();
+        let blit_bind_group = render_context.render_device().create_bind_group(
+            "compute_post_process_blit_bind_group",
+            &blit.texture_bind_group,
+            &BindGroupEntries::sequential((&view_compute.output.default_view, &blit.sampler)),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("compute_post_process_blit_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(blit_pipeline);
+        render_pass.set_bind_group(0, &blit_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+        drop(render_pass);
+
+        if let Some(blend) = blend {
+            blend.render(
+                render_context,
+                world,
+                post_process.source,
+                post_process.destination,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether the compute effect `T` runs on a view.
+fn is_compute_applied<T: ComputePostProcess>(world: &World, view: Entity) -> bool {
+    world.get_entity(view).is_ok_and(|view| {
+        view.contains::<ViewComputePostProcess<T>>()
+            && !is_weightless(view.get::<EffectWeight<T>>())
+    })
+}
+
+/// Adds the compute effect `T`.
+pub struct ComputePostProcessPlugin<T: ComputePostProcess>(PhantomData<fn() -> T>);
+
+impl<T: ComputePostProcess> Default for ComputePostProcessPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ComputePostProcess> Plugin for ComputePostProcessPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<T>::default(),
+            ExtractComponentPlugin::<ViewComputeShaderDefs<T>>::default(),
+            UniformComponentPlugin::<T::Out>::default(),
+            ExtractResourcePlugin::<ComputePostProcessImage<T>>::default(),
+            ExtractResourcePlugin::<EffectShader<T>>::default(),
+        ));
+
+        if !app.is_plugin_added::<EffectBlendPlugin<T>>() {
+            app.add_plugins(EffectBlendPlugin::<T>::default());
+        }
+
+        if T::view_bindings() {
+            add_view_bindings(app);
+        }
+
+        init_effect_shader::<T>(app, T::shader_path());
+
+        register_effect_node(
+            app,
+            T::Label::default(),
+            |world| {
+                Box::new(ViewNodeRunner::new(
+                    ComputePostProcessNode::<T>::default(),
+                    world,
+                ))
+            },
+            is_compute_applied::<T>,
+        );
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<SpecializedComputePipelines<ComputePostProcessPipeline<T>>>()
+            .init_resource::<EffectBindGroup<T>>()
+            .add_systems(
+                Render,
+                (
+                    prepare_compute_post_process::<T>.in_set(RenderSet::PrepareResources),
+                    prepare_compute_post_process_bind_group::<T>
+                        .in_set(RenderSet::PrepareBindGroups),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        app.init_resource::<ComputePostProcessImage<T>>();
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<ComputePostProcessPipeline<T>>();
+    }
+}
@@ -0,0 +1,79 @@
+use bevy::{
+    diagnostic::DiagnosticPath,
+    prelude::*,
+    render::{
+        diagnostic::RecordDiagnostics,
+        render_graph::{InternedRenderLabel, RenderLabel},
+        renderer::RenderContext,
+    },
+};
+
+/// The name of an effect's passes and pipelines, which is the name of its label.
+pub(crate) fn effect_name(label: impl RenderLabel) -> String {
+    format!("{label:?}")
+}
+
+/// The name of a fused pass rendering several effects, see [`super::fusion::PostProcessFusion`].
+pub(crate) fn fused_effect_name(labels: &[InternedRenderLabel]) -> String {
+    labels
+        .iter()
+        .map(|label| effect_name(*label))
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+fn span_name(camera: Entity, name: &str) -> String {
+    format!("post_processing/{camera}/{name}")
+}
+
+/// The CPU time in milliseconds an effect took to record on a camera, in [`DiagnosticsStore`].
+///
+/// Effects are only measured with Bevy's [`RenderDiagnosticsPlugin`](bevy::render::diagnostic::RenderDiagnosticsPlugin):
+///
+/// ```rust,ignore
+/// app.add_plugins((RenderDiagnosticsPlugin, LogDiagnosticsPlugin::default()));
+///
+/// fn wave_cost(store: Res<DiagnosticsStore>, camera: Single<Entity, With<Wave>>) {
+///     let path = effect_elapsed_gpu(*camera, WavePostProcessLabel);
+///     if let Some(ms) = store.get(&path).and_then(Diagnostic::smoothed) {
+///         info!("Wave takes {ms:.3}ms");
+///     }
+/// }
+/// ```
+///
+/// Effects fused into a single pass are measured together, under their labels joined with `+`.
+///
+/// [`DiagnosticsStore`]: bevy::diagnostic::DiagnosticsStore
+pub fn effect_elapsed_cpu(camera: Entity, label: impl RenderLabel) -> DiagnosticPath {
+    DiagnosticPath::new(format!(
+        "render/{}/elapsed_cpu",
+        span_name(camera, &effect_name(label))
+    ))
+}
+
+/// The GPU time in milliseconds an effect took to render on a camera, in [`DiagnosticsStore`].
+///
+/// GPU timings need timestamp queries, which are only supported on Vulkan and DX12.
+/// See [`effect_elapsed_cpu`].
+///
+/// [`DiagnosticsStore`]: bevy::diagnostic::DiagnosticsStore
+pub fn effect_elapsed_gpu(camera: Entity, label: impl RenderLabel) -> DiagnosticPath {
+    DiagnosticPath::new(format!(
+        "render/{}/elapsed_gpu",
+        span_name(camera, &effect_name(label))
+    ))
+}
+
+/// Renders effects on a camera within a diagnostic span measuring their cost.
+pub(crate) fn in_effect_span<'w, R>(
+    render_context: &mut RenderContext<'w>,
+    camera: Entity,
+    name: &str,
+    render: impl FnOnce(&mut RenderContext<'w>) -> R,
+) -> R {
+    let diagnostics = render_context.diagnostic_recorder();
+    let span = diagnostics.time_span(render_context.command_encoder(), span_name(camera, name));
+    let result = render(render_context);
+    span.end(render_context.command_encoder());
+    result
+}
@@ -9,9 +9,16 @@ use binding_types::uniform_buffer;
 
 use std::fmt::Display;
 
-use super::simple_post_process::{SimplePostProcess, TextureInputs};
+use super::{
+    fusion::{FusionStage, FusionStageKind},
+    shader::embedded_shader_path,
+    simple_post_process::{SimplePostProcess, TextureInputs},
+};
 ///TODO
-#[derive(Component, Default, Clone, Copy, ExtractComponent, ShaderType)]
+#[derive(Component, Default, Clone, Copy, ExtractComponent, ShaderType, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub struct FlipUniform {
     pub(crate) x: f32,
     pub(crate) y: f32,
@@ -31,7 +38,10 @@ impl From<Flip> for FlipUniform {
 }
 
 /// Which way to flip the texture.
-#[derive(Debug, Default, Copy, Clone, Component)]
+#[derive(Debug, Default, Copy, Clone, Component, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub enum Flip {
     /// Don't flip.
     None,
@@ -55,7 +65,7 @@ impl Display for Flip {
 
 impl SimplePostProcess for FlipUniform {
     fn shader_path() -> String {
-        concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/", "flip.wgsl").into()
+        embedded_shader_path("flip.wgsl")
     }
     type Label = FlipPostProcessLabel;
     fn layout(device: &RenderDevice) -> BindGroupLayout {
@@ -73,12 +83,22 @@ impl SimplePostProcess for FlipUniform {
         layout: &BindGroupLayout,
         buffer: BindingResource,
         _textures: &TextureInputs,
-    ) -> BindGroup {
-        device.create_bind_group(
+    ) -> Option<BindGroup> {
+        Some(device.create_bind_group(
             "flip_bind_group",
             layout,
             &BindGroupEntries::sequential((buffer,)),
-        )
+        ))
+    }
+
+    fn fusion_stage() -> Option<FusionStage> {
+        Some(FusionStage {
+            module: embedded_shader_path("stages/flip.wgsl"),
+            import_path: "bevy_vfx_bag::flip",
+            uniform: "Flip",
+            function: "flip_uv",
+            kind: FusionStageKind::Uv,
+        })
     }
 }
 
@@ -0,0 +1,589 @@
+use std::{fmt::Write, num::NonZeroU64};
+
+use bevy::{
+    asset::AssetPath,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+        },
+        render_graph::InternedRenderLabel,
+        render_resource::*,
+        renderer::{RenderContext, RenderDevice},
+        view::ViewTarget,
+        ExtractSchedule, MainWorld, Render, RenderApp, RenderSet,
+    },
+    utils::HashMap,
+};
+
+use super::{
+    blend::ViewEffectBlend,
+    clock::globals_binding,
+    diagnostics::fused_effect_name,
+    resolution::ViewEffectResolution,
+    shader::EffectShader,
+    simple_post_process::{
+        post_process_pipeline_descriptor, shared_layout, PostProcessPipelineKey, SimplePostProcess,
+        ViewPostProcessShaderDefs,
+    },
+    stack::{PostProcessEffects, PostProcessingOrder},
+};
+
+/// Renders adjacent compatible effects of a camera in a single pass.
+///
+/// Without this each effect reads and writes the whole screen once.
+/// With it, runs of effects which only move UVs or change colors, like
+/// [`super::flip::FlipUniform`], [`super::wave::Wave`], [`super::pixelate::Pixelate`] and
+/// [`super::masks::Mask`], are combined into one generated shader.
+/// The result matches rendering the effects one by one, up to filtering differences.
+///
+/// Effects which are partially applied (see [`super::blend`]), render at reduced resolution
+/// (see [`super::resolution`]) or use a replaced [`EffectShader`] are never fused.
+#[derive(Debug, Default, Clone, Copy, Component, ExtractComponent)]
+pub struct PostProcessFusion;
+
+/// Whether a [`FusionStage`] transforms UVs or colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FusionStageKind {
+    /// The stage function has the signature
+    /// `fn(uv: vec2<f32>, resolution: vec2<f32>, time: f32, settings: S) -> vec2<f32>`
+    /// and returns where to sample the input for an output UV.
+    Uv,
+    /// The stage function has the signature
+    /// `fn(color: vec4<f32>, uv: vec2<f32>, resolution: vec2<f32>, time: f32, settings: S) -> vec4<f32>`
+    /// and returns the output color for the input color at a UV.
+    Color,
+}
+
+/// How an effect is rendered as part of a fused pass, see [`PostProcessFusion`].
+///
+/// The stage lives in a shader module which defines the effect's uniform struct and
+/// the stage function. The effect's own shader should use the same module,
+/// so both render the same.
+#[derive(Debug, Clone)]
+pub struct FusionStage {
+    /// The asset path of the shader module.
+    pub module: String,
+    /// The import path of the module, e.g. `bevy_vfx_bag::flip`.
+    pub import_path: &'static str,
+    /// The name of the uniform struct in the module.
+    pub uniform: &'static str,
+    /// The name of the stage function in the module.
+    pub function: &'static str,
+    /// What the stage function transforms.
+    pub kind: FusionStageKind,
+}
+
+/// What the stack needs to know to fuse a registered effect.
+#[derive(Clone)]
+pub(crate) struct FusableEffect {
+    stage: FusionStage,
+    min_binding_size: NonZeroU64,
+    can_fuse: fn(&World, Entity) -> bool,
+    shader_defs: fn(&World, Entity) -> Vec<ShaderDefVal>,
+    uniform_offset: fn(&World, Entity) -> Option<u32>,
+    uniforms: fn(&World) -> Option<(BufferId, BindingResource<'_>)>,
+}
+
+impl FusableEffect {
+    pub(crate) fn new<T: SimplePostProcess>(stage: FusionStage) -> Self {
+        Self {
+            stage,
+            min_binding_size: T::Out::min_size(),
+            can_fuse: |world, view| {
+                let default_shader = world.resource::<EffectShader<T>>().handle.path()
+                    == Some(&AssetPath::parse(&T::shader_path()));
+
+                default_shader
+                    && !T::view_bindings()
+                    && world.get::<ViewEffectBlend<T>>(view).is_none()
+                    && world.get::<ViewEffectResolution<T>>(view).is_none()
+            },
+            shader_defs: |world, view| {
+                world
+                    .get::<ViewPostProcessShaderDefs<T>>(view)
+                    .map(|shader_defs| shader_defs.shader_defs.clone())
+                    .unwrap_or_default()
+            },
+            uniform_offset: |world, view| {
+                world
+                    .get::<DynamicUniformIndex<T::Out>>(view)
+                    .map(DynamicUniformIndex::index)
+            },
+            uniforms: |world| {
+                let uniforms = world.resource::<ComponentUniforms<T::Out>>().uniforms();
+                Some((uniforms.buffer()?.id(), uniforms.binding()?))
+            },
+        }
+    }
+}
+
+/// A step of the fragment shader generated for a run of fused effects.
+///
+/// [`fused_shader_source`] writes these as WGSL and `reference::fused` runs them
+/// on the CPU, so both fuse effects the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FusedStep {
+    /// Moves the UV through the UV stage at this index, clamped to the image.
+    Uv(usize),
+    /// Keeps the UV for the color stage at this index.
+    KeepUv(usize),
+    /// Samples the input at the UV.
+    Sample,
+    /// Changes the color through the color stage at this index, at the UV kept for it.
+    Color(usize),
+}
+
+/// The steps of a run of effects with the given stage kinds, in the order they run.
+pub(crate) fn fused_steps(kinds: &[FusionStageKind]) -> Vec<FusedStep> {
+    // UV stages are applied last to first, to find where to sample the input.
+    let uv_steps = kinds.iter().enumerate().rev().map(|(i, kind)| match kind {
+        FusionStageKind::Uv => FusedStep::Uv(i),
+        FusionStageKind::Color => FusedStep::KeepUv(i),
+    });
+    // Color stages are applied first to last, at the UV they would have been at.
+    let color_steps = kinds
+        .iter()
+        .enumerate()
+        .filter(|(_, kind)| **kind == FusionStageKind::Color)
+        .map(|(i, _)| FusedStep::Color(i));
+
+    uv_steps
+        .chain([FusedStep::Sample])
+        .chain(color_steps)
+        .collect()
+}
+
+/// A range of unique ids for the generated shaders.
+const FUSED_SHADER_BASE: u128 = 0x5c1e_8a02_6f3d_4b71_9e0c_a4d2_0000_0000;
+
+/// The generated shader for a run of effects.
+pub(crate) fn fused_shader_source(stages: &[&FusionStage]) -> String {
+    let mut source = String::from(
+        "#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput\n\
+         #import bevy_render::globals::Globals\n",
+    );
+    for stage in stages {
+        let _ = writeln!(
+            source,
+            "#import {}::{{{}, {}}}",
+            stage.import_path, stage.uniform, stage.function
+        );
+    }
+
+    source.push_str(
+        "\n@group(0) @binding(0)\nvar t: texture_2d<f32>;\n\
+         @group(0) @binding(1)\nvar ts: sampler;\n\
+         @group(0) @binding(2)\nvar<uniform> globals: Globals;\n\n",
+    );
+    for (i, stage) in stages.iter().enumerate() {
+        let _ = writeln!(
+            source,
+            "@group(1) @binding({i})\nvar<uniform> stage_{i}: {};",
+            stage.uniform
+        );
+    }
+
+    source.push_str(
+        "\n@fragment\n\
+         fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {\n\
+         \x20   let resolution = vec2<f32>(textureDimensions(t));\n\
+         \x20   let time = globals.time;\n\n\
+         \x20   // UV stages are applied last to first, to find where to sample the input.\n\
+         \x20   // Each is clamped like sampling the output of the effect before it would be.\n\
+         \x20   var uv = in.uv;\n",
+    );
+    let kinds = stages.iter().map(|stage| stage.kind).collect::<Vec<_>>();
+    for step in fused_steps(&kinds) {
+        let _ = match step {
+            FusedStep::Uv(i) => writeln!(
+                source,
+                "    uv = clamp({}(uv, resolution, time, stage_{i}), vec2(0.0), vec2(1.0));",
+                stages[i].function
+            ),
+            FusedStep::KeepUv(i) => writeln!(source, "    let uv_{i} = uv;"),
+            FusedStep::Sample => writeln!(
+                source,
+                "\n    // Color stages are applied first to last, at the UV they would have been at.\n\
+                 \x20   var color = textureSample(t, ts, uv);"
+            ),
+            FusedStep::Color(i) => writeln!(
+                source,
+                "    color = {}(color, uv_{i}, resolution, time, stage_{i});",
+                stages[i].function
+            ),
+        };
+    }
+    source.push_str("    return color;\n}\n");
+
+    source
+}
+
+/// The resources of a run of fused effects, shared by all views using that run.
+struct FusedChain {
+    layout: BindGroupLayout,
+    shader: Handle<Shader>,
+    pipelines: HashMap<(TextureFormat, Vec<ShaderDefVal>), CachedRenderPipelineId>,
+    /// The bind group and the uniform buffers it was created from.
+    bind_group: Option<(Vec<BufferId>, BindGroup)>,
+}
+
+/// All fused runs of effects seen so far.
+#[derive(Resource)]
+pub(crate) struct FusionPipelines {
+    shared_layout: BindGroupLayout,
+    sampler: Sampler,
+    chains: HashMap<Vec<InternedRenderLabel>, FusedChain>,
+    /// Generated shaders which are yet to be added to the main world.
+    pending_shaders: Vec<(AssetId<Shader>, Shader)>,
+}
+
+impl FromWorld for FusionPipelines {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        Self {
+            shared_layout: shared_layout(render_device),
+            sampler: render_device.create_sampler(&SamplerDescriptor::default()),
+            chains: HashMap::default(),
+            pending_shaders: Vec::new(),
+        }
+    }
+}
+
+impl FusionPipelines {
+    fn chain(
+        &mut self,
+        render_device: &RenderDevice,
+        labels: &[InternedRenderLabel],
+        effects: &[&FusableEffect],
+    ) -> &mut FusedChain {
+        let id = self.chains.len() as u128;
+
+        self.chains.entry(labels.to_vec()).or_insert_with(|| {
+            let entries = effects
+                .iter()
+                .enumerate()
+                .map(|(i, effect)| BindGroupLayoutEntry {
+                    binding: i as u32,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: Some(effect.min_binding_size),
+                    },
+                    count: None,
+                })
+                .collect::<Vec<_>>();
+            let layout =
+                render_device.create_bind_group_layout("fused_post_process_layout", &entries);
+
+            let stages = effects
+                .iter()
+                .map(|effect| &effect.stage)
+                .collect::<Vec<_>>();
+            let shader = Handle::weak_from_u128(FUSED_SHADER_BASE + id);
+            self.pending_shaders.push((
+                shader.id(),
+                Shader::from_wgsl(
+                    fused_shader_source(&stages),
+                    format!("bevy_vfx_bag/fused_{id}.wgsl"),
+                ),
+            ));
+
+            FusedChain {
+                layout,
+                shader,
+                pipelines: HashMap::default(),
+                bind_group: None,
+            }
+        })
+    }
+}
+
+/// A run of effects rendered in a single pass on a specific view.
+pub(crate) struct ViewFusedChain {
+    labels: Vec<InternedRenderLabel>,
+    pipeline: CachedRenderPipelineId,
+    offsets: Vec<u32>,
+}
+
+/// The fused runs of effects of a view.
+#[derive(Component)]
+pub(crate) struct ViewFusedChains {
+    chains: Vec<ViewFusedChain>,
+}
+
+impl ViewFusedChains {
+    /// The run starting with the given effect, if any.
+    pub(crate) fn starting_with(&self, label: InternedRenderLabel) -> Option<&ViewFusedChain> {
+        self.chains.iter().find(|chain| chain.labels[0] == label)
+    }
+}
+
+impl ViewFusedChain {
+    /// The effects of the run, in order.
+    pub(crate) fn labels(&self) -> &[InternedRenderLabel] {
+        &self.labels
+    }
+
+    /// Renders the run of effects.
+    ///
+    /// Returns `false` if it is not ready yet, in which case the effects should run one by one.
+    pub(crate) fn render(
+        &self,
+        render_context: &mut RenderContext,
+        world: &World,
+        view: Entity,
+        view_target: &ViewTarget,
+    ) -> bool {
+        let fusion = world.resource::<FusionPipelines>();
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(self.pipeline)
+        else {
+            return false;
+        };
+        let Some((_, bind_group)) = fusion
+            .chains
+            .get(&self.labels)
+            .and_then(|chain| chain.bind_group.as_ref())
+        else {
+            return false;
+        };
+        let Some(globals) = globals_binding(world, view) else {
+            return false;
+        };
+
+        let post_process = view_target.post_process_write();
+
+        let shared_bind_group = render_context.render_device().create_bind_group(
+            "post_process_bind_group",
+            &fusion.shared_layout,
+            &BindGroupEntries::sequential((post_process.source, &fusion.sampler, globals)),
+        );
+
+        let label = fused_effect_name(&self.labels);
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some(&label),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &shared_bind_group, &[]);
+        render_pass.set_bind_group(1, bind_group, &self.offsets);
+        render_pass.draw(0..3, 0..1);
+
+        true
+    }
+}
+
+/// Finds the runs of fusable effects of each view using [`PostProcessFusion`],
+/// and prepares their pipelines and bind groups.
+fn prepare_fused_chains(world: &mut World) {
+    let views = world
+        .query_filtered::<Entity, (With<ViewTarget>, With<PostProcessFusion>)>()
+        .iter(world)
+        .collect::<Vec<_>>();
+
+    world.resource_scope(|world, mut fusion: Mut<FusionPipelines>| {
+        let effects = world.resource::<PostProcessEffects>();
+        let render_device = world.resource::<RenderDevice>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        let mut view_chains = Vec::new();
+        for view in views {
+            let Some(view_target) = world.get::<ViewTarget>(view) else {
+                continue;
+            };
+
+            // Split the effects applied on the view into runs of fusable ones.
+            let mut runs = vec![Vec::new()];
+            for label in effects.order(world.get::<PostProcessingOrder>(view)) {
+                let Some(effect) = effects.get(label) else {
+                    continue;
+                };
+                if !(effect.is_applied)(world, view) {
+                    continue;
+                }
+
+                match &effect.fusion {
+                    Some(fusable) if (fusable.can_fuse)(world, view) => {
+                        if let Some(run) = runs.last_mut() {
+                            run.push((label, fusable));
+                        }
+                    }
+                    _ => runs.push(Vec::new()),
+                }
+            }
+
+            let mut chains = Vec::new();
+            for run in runs.into_iter().filter(|run| run.len() > 1) {
+                let (labels, fusables): (Vec<_>, Vec<_>) = run.into_iter().unzip();
+
+                let Some(offsets) = fusables
+                    .iter()
+                    .map(|fusable| (fusable.uniform_offset)(world, view))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    continue;
+                };
+                let Some(uniforms) = fusables
+                    .iter()
+                    .map(|fusable| (fusable.uniforms)(world))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    continue;
+                };
+
+                let shared_layout = fusion.shared_layout.clone();
+                let chain = fusion.chain(render_device, &labels, &fusables);
+
+                let buffers = uniforms.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+                if chain
+                    .bind_group
+                    .as_ref()
+                    .is_none_or(|(cached, _)| *cached != buffers)
+                {
+                    let entries = uniforms
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, (_, resource))| BindGroupEntry {
+                            binding: i as u32,
+                            resource,
+                        })
+                        .collect::<Vec<_>>();
+                    let bind_group = render_device.create_bind_group(
+                        "fused_post_process_bind_group",
+                        &chain.layout,
+                        &entries,
+                    );
+                    chain.bind_group = Some((buffers, bind_group));
+                }
+
+                let shader_defs = fusables
+                    .iter()
+                    .flat_map(|fusable| (fusable.shader_defs)(world, view))
+                    .collect::<Vec<_>>();
+                let texture_format = view_target.main_texture_format();
+                let layouts = vec![shared_layout, chain.layout.clone()];
+                let shader = chain.shader.clone();
+                let pipeline = *chain
+                    .pipelines
+                    .entry((texture_format, shader_defs.clone()))
+                    .or_insert_with(|| {
+                        pipeline_cache.queue_render_pipeline(post_process_pipeline_descriptor(
+                            fused_effect_name(&labels),
+                            layouts,
+                            PostProcessPipelineKey {
+                                shader,
+                                texture_format,
+                                shader_defs,
+                                view_bindings: None,
+                            },
+                            None,
+                        ))
+                    });
+
+                chains.push(ViewFusedChain {
+                    labels,
+                    pipeline,
+                    offsets,
+                });
+            }
+
+            view_chains.push((view, chains));
+        }
+
+        for (view, chains) in view_chains {
+            world.entity_mut(view).insert(ViewFusedChains { chains });
+        }
+    });
+}
+
+/// Moves generated shaders to the main world, from where they are extracted like any other shader.
+fn extract_fused_shaders(mut main_world: ResMut<MainWorld>, mut fusion: ResMut<FusionPipelines>) {
+    if fusion.pending_shaders.is_empty() {
+        return;
+    }
+
+    let mut shaders = main_world.resource_mut::<Assets<Shader>>();
+    for (id, shader) in fusion.pending_shaders.drain(..) {
+        shaders.insert(id, shader);
+    }
+}
+
+/// Adds [`PostProcessFusion`] support.
+///
+/// Added along with the effect stack, so it does not need to be added manually.
+#[derive(Debug, Default)]
+pub(crate) struct PostProcessFusionPlugin;
+
+impl Plugin for PostProcessFusionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ExtractComponentPlugin::<PostProcessFusion>::default());
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .add_systems(
+                ExtractSchedule,
+                extract_fused_shaders.run_if(resource_exists::<FusionPipelines>),
+            )
+            .add_systems(
+                Render,
+                prepare_fused_chains.in_set(RenderSet::PrepareBindGroups),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<FusionPipelines>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post_processing::{flip::FlipUniform, masks::Mask, wave::Wave};
+
+    #[test]
+    fn fused_shader_runs_stages_in_order() {
+        let stages = [
+            Wave::fusion_stage(),
+            Mask::fusion_stage(),
+            FlipUniform::fusion_stage(),
+        ]
+        .map(|stage| stage.expect("Should be fusable"));
+        let source = fused_shader_source(&stages.iter().collect::<Vec<_>>());
+
+        let body = source
+            .split_once("var uv = in.uv;\n")
+            .expect("Should start from the output UV")
+            .1;
+        assert_eq!(
+            body,
+            "    uv = clamp(flip_uv(uv, resolution, time, stage_2), vec2(0.0), vec2(1.0));\n\
+             \x20   let uv_1 = uv;\n\
+             \x20   uv = clamp(wave_uv(uv, resolution, time, stage_0), vec2(0.0), vec2(1.0));\n\
+             \n\
+             \x20   // Color stages are applied first to last, at the UV they would have been at.\n\
+             \x20   var color = textureSample(t, ts, uv);\n\
+             \x20   color = mask_color(color, uv_1, resolution, time, stage_1);\n\
+             \x20   return color;\n\
+             }\n"
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::tween::EffectLerp;
+
+/// Effects which can be fired as an [`EffectImpulse`].
+pub trait ImpulseEffect: EffectLerp {
+    /// This effect with no visible impact, keeping the parameters which shape it,
+    /// e.g. a [`super::wave::Wave`] without amplitude.
+    ///
+    /// Impulses on a camera without the effect rise from and decay to this.
+    fn at_rest(&self) -> Self;
+}
+
+/// How the strength of an [`EffectImpulse`] develops over time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImpulseEnvelope {
+    /// Seconds to rise to full strength.
+    pub attack: f32,
+    /// Seconds at full strength.
+    pub hold: f32,
+    /// Seconds to decay to nothing.
+    pub release: f32,
+    /// The easing of the attack, and of the release played backwards.
+    pub ease: EaseFunction,
+}
+
+impl ImpulseEnvelope {
+    /// An envelope with the given attack, hold and release, eased linearly.
+    pub fn new(attack: f32, hold: f32, release: f32) -> Self {
+        Self {
+            attack: attack.max(0.0),
+            hold: hold.max(0.0),
+            release: release.max(0.0),
+            ease: EaseFunction::Linear,
+        }
+    }
+
+    /// A hit: an instant attack, decaying over the given seconds.
+    pub fn hit(release: f32) -> Self {
+        Self::new(0.0, 0.0, release).with_ease(EaseFunction::QuadraticIn)
+    }
+
+    /// Eases the envelope with the given function.
+    pub fn with_ease(mut self, ease: EaseFunction) -> Self {
+        self.ease = ease;
+        self
+    }
+
+    /// Seconds until the impulse fully decayed.
+    pub fn duration(&self) -> f32 {
+        self.attack + self.hold + self.release
+    }
+
+    /// The strength the given seconds into the impulse, within `0.0..=1.0` for non-overshooting easing.
+    pub fn strength(&self, time: f32) -> f32 {
+        let progress = if time < 0.0 {
+            0.0
+        } else if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.hold {
+            1.0
+        } else if time < self.duration() {
+            1.0 - (time - self.attack - self.hold) / self.release
+        } else {
+            0.0
+        };
+
+        EasingCurve::new(0.0, 1.0, self.ease).sample_clamped(progress)
+    }
+}
+
+impl Default for ImpulseEnvelope {
+    fn default() -> Self {
+        Self::hit(0.5)
+    }
+}
+
+/// Fires a transient effect `T` on a camera.
+///
+/// ```rust,ignore
+/// impulses.send(EffectImpulse::new(
+///     camera,
+///     ChromaticAberration { magnitude_r: 0.02, ..default() },
+///     ImpulseEnvelope::hit(0.3),
+/// ));
+/// ```
+///
+/// The effect rises to `peak` and decays back over the envelope, from the camera's current
+/// effect if it has one, or from [`ImpulseEffect::at_rest`] otherwise, in which case the
+/// effect is removed again once decayed.
+///
+/// Overlapping impulses stack: two at full strength push the effect twice as far.
+/// Changes to the effect while impulses play are overwritten.
+#[derive(Debug, Clone, Event)]
+pub struct EffectImpulse<T: ImpulseEffect> {
+    /// The camera to fire the effect on.
+    pub camera: Entity,
+    /// The effect at full strength.
+    pub peak: T,
+    /// How the strength develops over time.
+    pub envelope: ImpulseEnvelope,
+}
+
+impl<T: ImpulseEffect> EffectImpulse<T> {
+    /// An impulse of the given effect on the given camera.
+    pub fn new(camera: Entity, peak: T, envelope: ImpulseEnvelope) -> Self {
+        Self {
+            camera,
+            peak,
+            envelope,
+        }
+    }
+}
+
+/// The [`EffectImpulse`]s playing on a camera.
+#[derive(Debug, Clone, Component)]
+pub struct ActiveImpulses<T: ImpulseEffect> {
+    /// The effect before the impulses, if the camera had one.
+    base: Option<T>,
+    /// What the impulses rise from.
+    rest: T,
+    impulses: Vec<(EffectImpulse<T>, f32)>,
+}
+
+impl<T: ImpulseEffect> ActiveImpulses<T> {
+    /// How many impulses are playing.
+    pub fn len(&self) -> usize {
+        self.impulses.len()
+    }
+
+    /// Whether no impulses are playing.
+    pub fn is_empty(&self) -> bool {
+        self.impulses.is_empty()
+    }
+
+    /// The effect with all impulses applied at their current strength.
+    fn value(&self) -> T {
+        // Stacking impulses add up their offsets from rest. With only `lerp` available,
+        // that is a lerp towards the strength-weighted average peak by the total strength.
+        let mut total = 0.0;
+        let mut peak: Option<T> = None;
+
+        for (impulse, elapsed) in &self.impulses {
+            let strength = impulse.envelope.strength(*elapsed);
+            total += strength;
+            peak = Some(match peak {
+                Some(peak) if total != 0.0 => peak.lerp(&impulse.peak, strength / total),
+                Some(peak) => peak,
+                None => impulse.peak.clone(),
+            });
+        }
+
+        let from = self.base.as_ref().unwrap_or(&self.rest);
+        match peak {
+            Some(peak) => from.lerp(&peak, total),
+            None => from.clone(),
+        }
+    }
+}
+
+fn start_effect_impulses<T: ImpulseEffect>(
+    mut commands: Commands,
+    mut events: EventReader<EffectImpulse<T>>,
+    mut cameras: Query<(Option<&mut ActiveImpulses<T>>, Option<&T>)>,
+) {
+    let mut started = HashMap::<Entity, ActiveImpulses<T>>::default();
+
+    for event in events.read() {
+        let Ok((active, effect)) = cameras.get_mut(event.camera) else {
+            warn!(
+                "Effect impulse on {} ignored, it does not exist",
+                event.camera
+            );
+            continue;
+        };
+
+        let impulse = (event.clone(), 0.0);
+        match active {
+            Some(mut active) => active.impulses.push(impulse),
+            None => started
+                .entry(event.camera)
+                .or_insert_with(|| ActiveImpulses {
+                    base: effect.cloned(),
+                    rest: event.peak.at_rest(),
+                    impulses: vec![],
+                })
+                .impulses
+                .push(impulse),
+        }
+    }
+
+    for (camera, active) in started {
+        commands.entity(camera).insert(active);
+    }
+}
+
+fn decay_effect_impulses<T: ImpulseEffect>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cameras: Query<(Entity, &mut ActiveImpulses<T>, Option<&mut T>)>,
+) {
+    for (camera, mut active, effect) in &mut cameras {
+        for (impulse, elapsed) in &mut active.impulses {
+            *elapsed = (*elapsed + time.delta_secs()).min(impulse.envelope.duration());
+        }
+
+        let value = if active
+            .impulses
+            .iter()
+            .all(|(impulse, elapsed)| *elapsed >= impulse.envelope.duration())
+        {
+            let mut entity = commands.entity(camera);
+            entity.remove::<ActiveImpulses<T>>();
+
+            match active.base.take() {
+                Some(base) => base,
+                None => {
+                    entity.remove::<T>();
+                    continue;
+                }
+            }
+        } else {
+            active
+                .impulses
+                .retain(|(impulse, elapsed)| *elapsed < impulse.envelope.duration());
+            active.value()
+        };
+
+        match effect {
+            Some(mut effect) => *effect = value,
+            None => {
+                commands.entity(camera).insert(value);
+            }
+        }
+    }
+}
+
+/// Fires the effect `T` via [`EffectImpulse<T>`] events.
+///
+/// Added for the effects of this crate by [`super::PostProcessingPlugin`].
+/// Add it for custom effects implementing [`ImpulseEffect`].
+pub struct EffectImpulsePlugin<T: ImpulseEffect>(PhantomData<T>);
+
+impl<T: ImpulseEffect> Default for EffectImpulsePlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ImpulseEffect> Plugin for EffectImpulsePlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EffectImpulse<T>>().add_systems(
+            Update,
+            (start_effect_impulses::<T>, decay_effect_impulses::<T>).chain(),
+        );
+    }
+}
@@ -5,7 +5,10 @@ use bevy::{
     render::{extract_component::ExtractComponent, render_graph::RenderLabel, render_resource::*},
 };
 
-use super::post_process::{GetShaderDefs, PostProcess};
+use super::{
+    post_process::{GetShaderDefs, PostProcess},
+    shader::embedded_shader_path,
+};
 ///TODO
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
 pub struct LutPostProcessLabel;
@@ -27,7 +30,7 @@ impl GetShaderDefs for LutPostProcessBindGroup {
 
 impl PostProcess for Lut {
     fn shader_path() -> String {
-        concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/", "lut.wgsl").into()
+        embedded_shader_path("lut.wgsl")
     }
     type Label = LutPostProcessLabel;
 
@@ -64,7 +67,8 @@ impl PostProcess for Lut {
 }
 
 /// A look-up texture. Maps colors to colors. Useful for colorschemes.
-#[derive(Default, Debug, Component, Clone, ExtractComponent)]
+#[derive(Default, Debug, Component, Clone, ExtractComponent, Reflect)]
+#[reflect(Component, Default)]
 pub struct Lut {
     /// The 3D look-up texture
     handle: Handle<LutPostProcessBindGroup>,
@@ -99,6 +103,12 @@ impl Lut {
     pub fn slate(assets: &mut Assets<LutPostProcessBindGroup>) -> Self {
         Self::new(assets, LUT_SLATE_IMAGE_HANDLE)
     }
+
+    /// Loads a 3D look-up texture for [`Self::new`] from a PNG file,
+    /// laid out like the output of the `make-neutral-lut` example.
+    pub fn image_from_png(buffer: &[u8]) -> Image {
+        adapt_image_for_lut_use(buffer)
+    }
 }
 
 const LUT_ARCTIC_IMAGE_HANDLE: Handle<Image> = Handle::weak_from_u128(11514769687270273032);
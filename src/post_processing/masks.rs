@@ -1,4 +1,5 @@
 use bevy::{
+    ecs::query::QueryItem,
     prelude::*,
     render::{
         extract_component::ExtractComponent, render_graph::RenderLabel, render_resource::*,
@@ -7,10 +8,19 @@ use bevy::{
 };
 use binding_types::uniform_buffer;
 
-use super::simple_post_process::{SimplePostProcess, TextureInputs};
+use super::{
+    fusion::{FusionStage, FusionStageKind},
+    impulse::ImpulseEffect,
+    shader::embedded_shader_path,
+    simple_post_process::{SimplePostProcess, TextureInputs},
+    tween::EffectLerp,
+};
 
 /// This controls the parameters of the effect.
-#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+#[derive(Debug, Default, Eq, PartialEq, Hash, Copy, Clone, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub enum MaskVariant {
     /// Rounded square type mask.
     ///
@@ -44,6 +54,7 @@ pub enum MaskVariant {
     ///
     /// Low end:    0.10 gives a very subtle effect.
     /// High end:   1.50 is almost a spotlight in the middle of the screen.
+    #[default]
     Vignette,
 }
 
@@ -58,24 +69,45 @@ impl From<MaskVariant> for ShaderDefVal {
     }
 }
 
-impl From<&ShaderDefVal> for MaskVariant {
-    fn from(value: &ShaderDefVal) -> Self {
+impl TryFrom<&ShaderDefVal> for MaskVariant {
+    type Error = UnknownMaskVariant;
+
+    fn try_from(value: &ShaderDefVal) -> Result<Self, Self::Error> {
         match value {
             ShaderDefVal::Bool(key, _) => match key.as_str() {
-                "SQUARE" => MaskVariant::Square,
-                "CRT" => MaskVariant::Crt,
-                "VIGNETTE" => MaskVariant::Vignette,
-                _ => panic!("Unknown ShaderDefVal key: {}", key),
+                "SQUARE" => Ok(MaskVariant::Square),
+                "CRT" => Ok(MaskVariant::Crt),
+                "VIGNETTE" => Ok(MaskVariant::Vignette),
+                _ => Err(UnknownMaskVariant(value.clone())),
             },
-            ShaderDefVal::Int(key, _) | ShaderDefVal::UInt(key, _) => {
-                panic!("ShaderDefVal type not supported for MaskVariant: {}", key)
+            ShaderDefVal::Int(..) | ShaderDefVal::UInt(..) => {
+                Err(UnknownMaskVariant(value.clone()))
             }
         }
     }
 }
 
+/// A shader definition which does not name a [`MaskVariant`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownMaskVariant(pub ShaderDefVal);
+
+impl std::fmt::Display for UnknownMaskVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown mask variant shader def: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownMaskVariant {}
+
 /// A darkening mask on the outer edges of the image.
-#[derive(Component, Clone, Copy, ExtractComponent, ShaderType)]
+#[derive(Debug, Component, Clone, Copy, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub struct Mask {
     /// The strength parameter of the mask in use.
     ///
@@ -87,14 +119,41 @@ pub struct Mask {
 
     /// How much the mask is faded: 1.0 - mask has no effect, 0.0 - mask is in full effect
     pub fade: f32,
-    // Which [`MaskVariant`] to produce.
-    //pub variant: MaskVariant,
+
+    /// Which [`MaskVariant`] to produce.
+    pub variant: MaskVariant,
+}
+
+/// The part of [`Mask`] passed to the shader.
+/// The variant is passed as a shader definition instead.
+#[derive(Component, Clone, Copy, ShaderType)]
+#[cfg_attr(test, derive(Reflect))]
+pub struct MaskUniform {
+    strength: f32,
+    fade: f32,
+}
+
+impl ExtractComponent for Mask {
+    type QueryData = &'static Self;
+    type QueryFilter = ();
+    type Out = MaskUniform;
+
+    fn extract_component(mask: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        Some(MaskUniform {
+            strength: mask.strength,
+            fade: mask.fade,
+        })
+    }
 }
 
 use std::fmt::Display;
 impl Display for Mask {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, " strength: {} fade: {}", self.strength, self.fade)
+        write!(
+            f,
+            "{:?} strength: {} fade: {}",
+            self.variant, self.strength, self.fade
+        )
     }
 }
 
@@ -104,7 +163,7 @@ impl Mask {
         Self {
             strength: 20.,
             fade: 0.,
-            //variant: MaskVariant::Square,
+            variant: MaskVariant::Square,
         }
     }
 
@@ -113,7 +172,7 @@ impl Mask {
         Self {
             strength: 80000.,
             fade: 0.,
-            //variant: MaskVariant::Crt,
+            variant: MaskVariant::Crt,
         }
     }
 
@@ -122,7 +181,7 @@ impl Mask {
         Self {
             strength: 0.66,
             fade: 0.,
-            //variant: MaskVariant::Vignette,
+            variant: MaskVariant::Vignette,
         }
     }
 }
@@ -133,9 +192,30 @@ impl Default for Mask {
     }
 }
 
+impl EffectLerp for Mask {
+    /// The variant can not be interpolated, so it switches to `other`'s once `t` reaches 1.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            strength: self.strength.lerp(other.strength, t),
+            fade: self.fade.lerp(other.fade, t),
+            variant: if t < 1.0 { self.variant } else { other.variant },
+        }
+    }
+
+    fn can_lerp(&self, other: &Self) -> bool {
+        self.variant == other.variant
+    }
+}
+
+impl ImpulseEffect for Mask {
+    fn at_rest(&self) -> Self {
+        Self { fade: 1.0, ..*self }
+    }
+}
+
 impl SimplePostProcess for Mask {
     fn shader_path() -> String {
-        concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/", "masks.wgsl").into()
+        embedded_shader_path("masks.wgsl")
     }
     type Label = MaskPostProcessLabel;
     fn layout(device: &RenderDevice) -> BindGroupLayout {
@@ -143,7 +223,7 @@ impl SimplePostProcess for Mask {
             "mask_bind_group_layout",
             &BindGroupLayoutEntries::sequential(
                 ShaderStages::FRAGMENT,
-                (uniform_buffer::<Self>(true),),
+                (uniform_buffer::<MaskUniform>(true),),
             ),
         )
     }
@@ -153,15 +233,25 @@ impl SimplePostProcess for Mask {
         layout: &BindGroupLayout,
         buffer: BindingResource,
         _textures: &TextureInputs,
-    ) -> BindGroup {
-        device.create_bind_group(
+    ) -> Option<BindGroup> {
+        Some(device.create_bind_group(
             "mask_bind_group",
             layout,
             &BindGroupEntries::sequential((buffer,)),
-        )
+        ))
+    }
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        vec![self.variant.into()]
     }
-    fn shader_defs() -> Vec<ShaderDefVal> {
-        vec!["VIGNETTE".into()]
+
+    fn fusion_stage() -> Option<FusionStage> {
+        Some(FusionStage {
+            module: embedded_shader_path("stages/masks.wgsl"),
+            import_path: "bevy_vfx_bag::masks",
+            uniform: "Mask",
+            function: "mask_color",
+            kind: FusionStageKind::Color,
+        })
     }
 }
 ///TODO
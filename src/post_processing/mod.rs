@@ -1,23 +1,85 @@
 use bevy::{
-    core_pipeline::core_3d::graph::{Core3d, Node3d},
     prelude::*,
-    render::{render_graph::RenderGraphApp, RenderApp},
+    render::{render_graph::RenderLabel, RenderApp},
 };
 use blur::{Blur, BlurPostProcessLabel};
 use chromatic_aberration::{ChromaticAberration, ChromaticAberrationPostProcessLabel};
-use flip::{FlipPostProcessLabel, FlipUniform};
+use flip::{Flip, FlipPostProcessLabel, FlipUniform};
+use impulse::EffectImpulsePlugin;
 use lut::{Lut, LutPostProcessLabel};
-use masks::{Mask, MaskPostProcessLabel};
+use masks::{Mask, MaskPostProcessLabel, MaskVariant};
 use pixelate::{Pixelate, PixelatePostProcessLabel};
 use post_process::PostProcessPlugin;
 use raindrops::{Raindrops, RaindropsPostProcessLabel};
+use screen_transition::{
+    update_transition_noise, EdgeGlow, TileShape, Transition, TransitionMode,
+    TransitionPostProcessLabel,
+};
 use simple_post_process::SimplePostProcessPlugin;
+use stack::PostProcessEffects;
 use test::{TestPostProcessLabel, TestPostProcessSettings};
+use transition::EffectTransitionPlugin;
+use tween::EffectTweenPlugin;
 use wave::{Wave, WavePostProcessLabel};
 
 ///TODO
 pub mod simple_post_process;
 
+/// Partially applying effects.
+pub mod blend;
+
+/// Rendering effects at reduced resolution.
+pub mod resolution;
+
+/// Where effects get their shaders from.
+pub mod shader;
+
+/// Running effects in a per-camera order.
+pub mod stack;
+
+/// Rendering several effects in a single pass.
+pub mod fusion;
+
+/// Effects rendered in several passes.
+pub mod multi_pass;
+
+/// Effects running as compute shaders.
+pub mod compute;
+
+/// Effects reading the view's uniform and prepass textures.
+pub mod view_bindings;
+
+/// Controlling the time effects see.
+pub mod clock;
+
+/// Reporting effects which could not be rendered.
+pub mod skipped;
+
+/// Measuring what effects cost to render.
+pub mod diagnostics;
+
+/// Comparing the image before and after effects.
+pub mod comparison;
+
+/// Animating effect parameters.
+pub mod tween;
+
+/// Firing transient effects.
+pub mod impulse;
+
+/// Changing between sets of effects over time.
+pub mod transition;
+
+/// Effect stacks loaded from asset files.
+#[cfg(feature = "presets")]
+pub mod preset;
+
+/// The effects computed on the CPU, to test against.
+///
+/// Each function runs an effect's shader over an sRGB image, as shown by an SDR camera.
+#[cfg(feature = "reference")]
+pub mod reference;
+
 ///TODO
 pub mod post_process;
 
@@ -48,12 +110,21 @@ pub mod blur;
 ///TODO
 pub mod chromatic_aberration;
 
+/// Fading, wiping and dissolving between scenes.
+pub mod screen_transition;
+
+/// Validating the shipped shaders for all their shader defs.
+#[cfg(test)]
+mod shader_validation;
+
 ///TODO
 #[derive(Debug, Default)]
 pub struct PostProcessingPlugin;
 
 impl Plugin for PostProcessingPlugin {
     fn build(&self, app: &mut App) {
+        shader::embed_shaders(app);
+
         app.add_plugins((
             SimplePostProcessPlugin::<TestPostProcessSettings>::default(),
             SimplePostProcessPlugin::<FlipUniform>::default(),
@@ -64,7 +135,52 @@ impl Plugin for PostProcessingPlugin {
             SimplePostProcessPlugin::<Blur>::default(),
             SimplePostProcessPlugin::<Wave>::default(),
             SimplePostProcessPlugin::<Pixelate>::default(),
+            SimplePostProcessPlugin::<Transition>::default(),
+        ))
+        .register_type::<FlipUniform>()
+        .register_type::<Flip>()
+        .register_type::<Raindrops>()
+        .register_type::<Mask>()
+        .register_type::<MaskVariant>()
+        .register_type::<Lut>()
+        .register_type::<ChromaticAberration>()
+        .register_type::<Blur>()
+        .register_type::<Wave>()
+        .register_type::<Pixelate>()
+        .register_type::<Transition>()
+        .register_type::<TransitionMode>()
+        .register_type::<TileShape>()
+        .register_type::<EdgeGlow>()
+        .add_systems(PostUpdate, update_transition_noise)
+        .add_plugins((
+            EffectTweenPlugin::<Raindrops>::default(),
+            EffectTweenPlugin::<Mask>::default(),
+            EffectTweenPlugin::<ChromaticAberration>::default(),
+            EffectTweenPlugin::<Blur>::default(),
+            EffectTweenPlugin::<Wave>::default(),
+            EffectTweenPlugin::<Pixelate>::default(),
+            EffectTweenPlugin::<Transition>::default(),
+        ))
+        .add_plugins((
+            EffectImpulsePlugin::<Mask>::default(),
+            EffectImpulsePlugin::<ChromaticAberration>::default(),
+            EffectImpulsePlugin::<Blur>::default(),
+            EffectImpulsePlugin::<Wave>::default(),
+            EffectImpulsePlugin::<Pixelate>::default(),
+        ))
+        .add_plugins((
+            EffectTransitionPlugin::<FlipUniform>::fading(),
+            EffectTransitionPlugin::<Raindrops>::interpolating(),
+            EffectTransitionPlugin::<Mask>::interpolating(),
+            EffectTransitionPlugin::<Lut>::fading(),
+            EffectTransitionPlugin::<ChromaticAberration>::interpolating(),
+            EffectTransitionPlugin::<Blur>::interpolating(),
+            EffectTransitionPlugin::<Wave>::interpolating(),
+            EffectTransitionPlugin::<Pixelate>::interpolating(),
         ));
+
+        #[cfg(feature = "presets")]
+        app.add_plugins(preset::VfxStackPlugin);
     }
 }
 
@@ -76,21 +192,20 @@ impl Plugin for PostProcessingDefaultOrderPlugin {
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
-        render_app.add_render_graph_edges(
-            Core3d,
-            (
-                Node3d::Tonemapping,
-                PixelatePostProcessLabel,
-                TestPostProcessLabel,
-                FlipPostProcessLabel,
-                RaindropsPostProcessLabel,
-                MaskPostProcessLabel,
-                LutPostProcessLabel,
-                ChromaticAberrationPostProcessLabel,
-                BlurPostProcessLabel,
-                WavePostProcessLabel,
-                Node3d::EndMainPassPostProcessing,
-            ),
-        );
+        render_app
+            .world_mut()
+            .resource_mut::<PostProcessEffects>()
+            .set_default_order(&[
+                PixelatePostProcessLabel.intern(),
+                TestPostProcessLabel.intern(),
+                FlipPostProcessLabel.intern(),
+                RaindropsPostProcessLabel.intern(),
+                MaskPostProcessLabel.intern(),
+                LutPostProcessLabel.intern(),
+                ChromaticAberrationPostProcessLabel.intern(),
+                BlurPostProcessLabel.intern(),
+                WavePostProcessLabel.intern(),
+                TransitionPostProcessLabel.intern(),
+            ]);
     }
 }
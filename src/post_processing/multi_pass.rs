@@ -0,0 +1,859 @@
+use std::{any::type_name, marker::PhantomData};
+
+use bevy::{
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        extract_resource::ExtractResourcePlugin,
+        render_graph::{NodeRunError, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner},
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::TextureCache,
+        view::ViewTarget,
+        Render, RenderApp, RenderSet,
+    },
+    utils::HashMap,
+};
+use encase::internal::WriteInto;
+
+use super::{
+    blend::{is_weightless, EffectBlendPlugin, EffectWeight, ViewEffectBlend},
+    clock::globals_binding,
+    diagnostics::effect_name,
+    shader::{init_effect_shader, EffectShader},
+    simple_post_process::{
+        post_process_pipeline_descriptor, shared_layout, PostProcessPipelineKey,
+    },
+    skipped::{report_skipped, SkipReason},
+    stack::register_effect_node,
+    view_bindings::{add_view_bindings, ViewBindings, ViewBindingsKey, ViewBindingsLayouts},
+};
+
+/// A post processing effect rendered in several passes.
+///
+/// Each pass is a fragment entry point of the effect's shader, which renders to either
+/// one of the effect's intermediate targets or, for the last pass, the view.
+/// Passes see the same bind groups as a [`super::simple_post_process::SimplePostProcess`]
+/// effect: the effect's input, a sampler and the globals at group 0, and the effect's
+/// uniform at group 1. Additionally the targets a pass reads are bound at group 2,
+/// followed by a linear sampler, and the view at group 3 if [`Self::view_bindings`].
+///
+/// ```rust,ignore
+/// impl MultiPassPostProcess for SeparableBlur {
+///     type Label = SeparableBlurLabel;
+///
+///     fn shader_path() -> String {
+///         "shaders/separable_blur.wgsl".into()
+///     }
+///
+///     fn targets() -> Vec<EffectTarget> {
+///         vec![EffectTarget::new("horizontal").with_size(TargetSize::Scale(0.5))]
+///     }
+///
+///     fn passes() -> Vec<EffectPass> {
+///         vec![
+///             EffectPass::new("horizontal").writing("horizontal"),
+///             EffectPass::new("vertical").reading("horizontal"),
+///         ]
+///     }
+/// }
+/// ```
+pub trait MultiPassPostProcess:
+    Component + Clone + Default + ExtractComponent<Out: Component + Clone + WriteInto + ShaderType>
+{
+    /// The label used to identify the post-processing effect.
+    type Label: RenderLabel + Default;
+    /// The shader path used for the post-processing effect.
+    fn shader_path() -> String;
+    /// The intermediate render targets the passes render to and read from.
+    fn targets() -> Vec<EffectTarget> {
+        vec![]
+    }
+    /// The passes, in the order they run. The last pass must render to the view.
+    ///
+    /// A pass may only read targets an earlier pass rendered to, or persistent ones, and not
+    /// the target it renders to itself.
+    fn passes() -> Vec<EffectPass>;
+    /// The shader definitions used for the post-processing effect.
+    ///
+    /// These are read per camera, so cameras may use different shader variants of the same effect.
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        vec![]
+    }
+    /// Whether the effect reads the view's uniform and prepass textures, bound at group 3.
+    ///
+    /// See [`ViewBindingsKey`] for how shaders access them.
+    fn view_bindings() -> bool {
+        false
+    }
+}
+
+/// An intermediate render target of a [`MultiPassPostProcess`] effect.
+#[derive(Debug, Clone)]
+pub struct EffectTarget {
+    /// The name passes refer to the target by.
+    pub name: &'static str,
+    /// The size of the target.
+    pub size: TargetSize,
+    /// The format of the target. `None` means the format of the view's main texture.
+    pub format: Option<TextureFormat>,
+    /// Keep the target's contents between frames.
+    ///
+    /// A pass reading the target before it is rendered to in a frame then sees the previous
+    /// frame's contents, which allows feedback effects. Other targets are pooled, so their
+    /// contents are undefined until rendered to.
+    pub persistent: bool,
+}
+
+impl EffectTarget {
+    /// A target at the view's size and format.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            size: TargetSize::default(),
+            format: None,
+            persistent: false,
+        }
+    }
+
+    /// Uses the given size.
+    pub fn with_size(mut self, size: TargetSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Uses the given format.
+    pub fn with_format(mut self, format: TextureFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Keeps the contents between frames, see [`Self::persistent`].
+    pub fn persistent(mut self) -> Self {
+        self.persistent = true;
+        self
+    }
+
+    fn descriptor(&self, view_target: &ViewTarget) -> TextureDescriptor<'static> {
+        let view_size = view_target.main_texture().size();
+        let (width, height) = match self.size {
+            TargetSize::Scale(scale) => (
+                (view_size.width as f32 * scale) as u32,
+                (view_size.height as f32 * scale) as u32,
+            ),
+            TargetSize::Fixed(size) => (size.x, size.y),
+        };
+
+        TextureDescriptor {
+            label: Some("effect_target_texture"),
+            size: Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: self
+                .format
+                .unwrap_or_else(|| view_target.main_texture_format()),
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        }
+    }
+}
+
+/// The size of an [`EffectTarget`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TargetSize {
+    /// Relative to the view's size.
+    Scale(f32),
+    /// A fixed size in pixels.
+    Fixed(UVec2),
+}
+
+impl Default for TargetSize {
+    fn default() -> Self {
+        Self::Scale(1.0)
+    }
+}
+
+/// A pass of a [`MultiPassPostProcess`] effect.
+#[derive(Debug, Clone)]
+pub struct EffectPass {
+    /// The fragment entry point in the effect's shader.
+    pub entry_point: &'static str,
+    /// The targets bound at group 2, in order.
+    pub inputs: Vec<&'static str>,
+    /// The target rendered to. `None` means the view.
+    pub output: Option<&'static str>,
+}
+
+impl EffectPass {
+    /// A pass running the given entry point and rendering to the view.
+    pub fn new(entry_point: &'static str) -> Self {
+        Self {
+            entry_point,
+            inputs: vec![],
+            output: None,
+        }
+    }
+
+    /// Binds the given target as the next input.
+    pub fn reading(mut self, target: &'static str) -> Self {
+        self.inputs.push(target);
+        self
+    }
+
+    /// Renders to the given target instead of the view.
+    pub fn writing(mut self, target: &'static str) -> Self {
+        self.output = Some(target);
+        self
+    }
+}
+
+/// The render pipelines of a multi pass effect.
+///
+/// The actual render pipelines are specialized per view and pass, see [`MultiPassPipelineKey`].
+#[derive(Resource)]
+pub struct MultiPassPipeline<T: MultiPassPostProcess> {
+    layouts: Vec<BindGroupLayout>,
+    input_layouts: Vec<BindGroupLayout>,
+    view_layouts: Option<ViewBindingsLayouts>,
+    sampler: Sampler,
+    input_sampler: Sampler,
+    passes: Vec<EffectPass>,
+    targets: Vec<EffectTarget>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: MultiPassPostProcess> FromWorld for MultiPassPipeline<T> {
+    fn from_world(world: &mut World) -> Self {
+        let view_layouts =
+            T::view_bindings().then(|| world.get_resource_or_init::<ViewBindingsLayouts>().clone());
+        let render_device = world.resource::<RenderDevice>();
+        let passes = T::passes();
+
+        let layouts = vec![
+            shared_layout(render_device),
+            render_device.create_bind_group_layout(
+                "multi_pass_bind_group_layout",
+                &BindGroupLayoutEntries::single(
+                    ShaderStages::FRAGMENT,
+                    uniform_buffer::<T::Out>(true),
+                ),
+            ),
+        ];
+        let input_layouts = passes
+            .iter()
+            .map(|pass| {
+                let mut entries = pass
+                    .inputs
+                    .iter()
+                    .map(|_| texture_2d(TextureSampleType::Float { filterable: true }))
+                    .collect::<Vec<_>>();
+                entries.push(sampler(SamplerBindingType::Filtering));
+
+                render_device.create_bind_group_layout(
+                    "multi_pass_inputs_bind_group_layout",
+                    &entries
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, entry)| entry.build(i as u32, ShaderStages::FRAGMENT))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+
+        Self {
+            layouts,
+            input_layouts,
+            view_layouts,
+            sampler: render_device.create_sampler(&SamplerDescriptor::default()),
+            input_sampler: render_device.create_sampler(&SamplerDescriptor {
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
+                ..default()
+            }),
+            passes,
+            targets: T::targets(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// What a multi pass render pipeline is specialized on.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MultiPassPipelineKey {
+    /// The index of the pass.
+    pub pass: usize,
+    /// The shader, format and shader definitions, as for single pass effects.
+    pub key: PostProcessPipelineKey,
+}
+
+/// The name of a pass of an effect, e.g. `SeparableBlurLabel::horizontal`.
+fn pass_name<T: MultiPassPostProcess>(entry_point: &str) -> String {
+    format!("{}::{entry_point}", effect_name(T::Label::default()))
+}
+
+impl<T: MultiPassPostProcess> SpecializedRenderPipeline for MultiPassPipeline<T> {
+    type Key = MultiPassPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let mut layouts = self.layouts.clone();
+        layouts.push(self.input_layouts[key.pass].clone());
+
+        let entry_point = self.passes[key.pass].entry_point;
+        let mut descriptor = post_process_pipeline_descriptor(
+            pass_name::<T>(entry_point),
+            layouts,
+            key.key,
+            self.view_layouts.as_ref(),
+        );
+        if let Some(fragment) = &mut descriptor.fragment {
+            fragment.entry_point = entry_point.into();
+        }
+
+        descriptor
+    }
+}
+
+/// The shader definitions a multi pass effect uses on a specific view.
+#[derive(Component)]
+pub struct ViewMultiPassShaderDefs<T> {
+    /// See [`MultiPassPostProcess::shader_defs`].
+    pub shader_defs: Vec<ShaderDefVal>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: MultiPassPostProcess> ExtractComponent for ViewMultiPassShaderDefs<T> {
+    type QueryData = &'static T;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(effect: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        Some(Self {
+            shader_defs: effect.shader_defs(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// The pipelines and targets of a multi pass effect on a specific view.
+#[derive(Component)]
+pub struct ViewMultiPass<T> {
+    pipelines: Vec<CachedRenderPipelineId>,
+    targets: HashMap<&'static str, TextureView>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// The persistent targets of a multi pass effect on a specific view.
+#[derive(Component)]
+struct ViewEffectHistory<T> {
+    textures: HashMap<&'static str, (TextureDescriptor<'static>, TextureView)>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+type ViewMultiPassQuery<T> = (
+    Entity,
+    &'static ViewTarget,
+    &'static ViewMultiPassShaderDefs<T>,
+    Option<&'static mut ViewEffectHistory<T>>,
+    Option<&'static ViewBindingsKey>,
+);
+
+fn prepare_multi_pass<T: MultiPassPostProcess>(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    mut texture_cache: ResMut<TextureCache>,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<MultiPassPipeline<T>>>,
+    multi_pass_pipeline: Res<MultiPassPipeline<T>>,
+    shader: Res<EffectShader<T>>,
+    mut views: Query<ViewMultiPassQuery<T>>,
+) {
+    for (entity, view_target, shader_defs, history, view_bindings) in &mut views {
+        let mut new_history = None;
+        let history = match history {
+            Some(history) => history.into_inner(),
+            None => new_history.insert(ViewEffectHistory::<T> {
+                textures: HashMap::default(),
+                _marker: PhantomData,
+            }),
+        };
+
+        let mut targets = HashMap::default();
+        for target in &multi_pass_pipeline.targets {
+            let descriptor = target.descriptor(view_target);
+
+            let view = if target.persistent {
+                let (_, view) = history
+                    .textures
+                    .entry(target.name)
+                    .and_modify(|(current, view)| {
+                        // Recreate the texture if the view was resized.
+                        if *current != descriptor {
+                            *current = descriptor.clone();
+                            *view = render_device
+                                .create_texture(&descriptor)
+                                .create_view(&TextureViewDescriptor::default());
+                        }
+                    })
+                    .or_insert_with(|| {
+                        let view = render_device
+                            .create_texture(&descriptor)
+                            .create_view(&TextureViewDescriptor::default());
+                        (descriptor, view)
+                    });
+                view.clone()
+            } else {
+                texture_cache.get(&render_device, descriptor).default_view
+            };
+
+            targets.insert(target.name, view);
+        }
+
+        let ids = multi_pass_pipeline
+            .passes
+            .iter()
+            .enumerate()
+            .map(|(pass, effect_pass)| {
+                let texture_format = effect_pass
+                    .output
+                    .and_then(|name| {
+                        multi_pass_pipeline
+                            .targets
+                            .iter()
+                            .find(|target| target.name == name)
+                    })
+                    .and_then(|target| target.format)
+                    .unwrap_or_else(|| view_target.main_texture_format());
+
+                pipelines.specialize(
+                    &pipeline_cache,
+                    &multi_pass_pipeline,
+                    MultiPassPipelineKey {
+                        pass,
+                        key: PostProcessPipelineKey {
+                            shader: shader.handle.clone(),
+                            texture_format,
+                            shader_defs: shader_defs.shader_defs.clone(),
+                            view_bindings: T::view_bindings()
+                                .then(|| view_bindings.copied().unwrap_or_default()),
+                        },
+                    },
+                )
+            })
+            .collect();
+
+        let mut entity = commands.entity(entity);
+        entity.insert(ViewMultiPass::<T> {
+            pipelines: ids,
+            targets,
+            _marker: PhantomData,
+        });
+        if let Some(history) = new_history {
+            entity.insert(history);
+        }
+    }
+}
+
+/// The uniform bind group of a multi pass effect, shared by all views.
+#[derive(Resource)]
+struct MultiPassBindGroup<T> {
+    bind_group: Option<(BufferId, BindGroup)>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for MultiPassBindGroup<T> {
+    fn default() -> Self {
+        Self {
+            bind_group: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Creates the effect's bind group, if the uniform buffer changed since last time.
+fn prepare_multi_pass_bind_group<T: MultiPassPostProcess>(
+    render_device: Res<RenderDevice>,
+    multi_pass_pipeline: Res<MultiPassPipeline<T>>,
+    uniforms: Res<ComponentUniforms<T::Out>>,
+    mut cached: ResMut<MultiPassBindGroup<T>>,
+) {
+    let uniforms = uniforms.uniforms();
+    let (Some(buffer), Some(binding)) = (uniforms.buffer(), uniforms.binding()) else {
+        cached.bind_group = None;
+        return;
+    };
+
+    if cached
+        .bind_group
+        .as_ref()
+        .is_some_and(|(id, _)| *id == buffer.id())
+    {
+        return;
+    }
+
+    let bind_group = render_device.create_bind_group(
+        "multi_pass_bind_group",
+        &multi_pass_pipeline.layouts[1],
+        &BindGroupEntries::single(binding),
+    );
+    cached.bind_group = Some((buffer.id(), bind_group));
+}
+
+/// Runs the passes of a multi pass effect on a view.
+#[derive(Default)]
+pub struct MultiPassPostProcessNode<T: MultiPassPostProcess>(PhantomData<fn() -> T>);
+
+impl<T: MultiPassPostProcess> ViewNode for MultiPassPostProcessNode<T> {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static DynamicUniformIndex<T::Out>,
+        &'static ViewMultiPass<T>,
+        Option<&'static EffectWeight<T>>,
+        Option<&'static ViewEffectBlend<T>>,
+        Option<&'static ViewBindings>,
+    );
+
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, settings_index, view_multi_pass, weight, blend, view_bindings): QueryItem<
+            Self::ViewQuery,
+        >,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        if is_weightless(weight) {
+            return Ok(());
+        }
+
+        let multi_pass_pipeline = world.resource::<MultiPassPipeline<T>>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        // All passes need to be ready, a partial effect is no effect.
+        let Some(pipelines) = view_multi_pass
+            .pipelines
+            .iter()
+            .map(|id| pipeline_cache.get_render_pipeline(*id))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Ok(());
+        };
+
+        // A partially applied effect is only shown once it can be blended with its input.
+        if blend.is_some_and(|blend| !blend.is_ready(world)) {
+            return Ok(());
+        }
+
+        // Effects reading the view wait for its prepass textures.
+        if T::view_bindings() && view_bindings.is_none() {
+            return Ok(());
+        }
+
+        let Some((_, bind_group)) = &world.resource::<MultiPassBindGroup<T>>().bind_group else {
+            report_skipped::<T>(
+                world,
+                graph.view_entity(),
+                T::Label::default(),
+                SkipReason::BindGroupUnavailable,
+            );
+            return Ok(());
+        };
+
+        let Some(globals) = globals_binding(world, graph.view_entity()) else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+
+        // The shared bind group holds the ping-pong source, so it is created each frame.
+        let shared_bind_group = render_context.render_device().create_bind_group(
+            "post_process_bind_group",
+            &multi_pass_pipeline.layouts[0],
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &multi_pass_pipeline.sampler,
+                globals,
+            )),
+        );
+
+        for (pass, (effect_pass, pipeline)) in
+            multi_pass_pipeline.passes.iter().zip(pipelines).enumerate()
+        {
+            let mut entries = effect_pass
+                .inputs
+                .iter()
+                .filter_map(|name| view_multi_pass.targets.get(name))
+                .map(|view| view.into_binding())
+                .collect::<Vec<_>>();
+            entries.push(multi_pass_pipeline.input_sampler.into_binding());
+
+            let inputs_bind_group = render_context.render_device().create_bind_group(
+                "multi_pass_inputs_bind_group",
+                &multi_pass_pipeline.input_layouts[pass],
+                &entries
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, resource)| BindGroupEntry {
+                        binding: i as u32,
+                        resource,
+                    })
+                    .collect::<Vec<_>>(),
+            );
+
+            let target: &TextureView = match effect_pass.output {
+                Some(name) => match view_multi_pass.targets.get(name) {
+                    Some(target) => target,
+                    None => continue,
+                },
+                None => post_process.destination,
+            };
+
+            let label = pass_name::<T>(effect_pass.entry_point);
+            let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                label: Some(&label),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_render_pipeline(pipeline);
+            render_pass.set_bind_group(0, &shared_bind_group, &[]);
+            render_pass.set_bind_group(1, bind_group, &[settings_index.index()]);
+            render_pass.set_bind_group(2, &inputs_bind_group, &[]);
+            if let Some(view_bindings) = view_bindings.filter(|_| T::view_bindings()) {
+                render_pass.set_bind_group(
+                    3,
+                    view_bindings.bind_group(),
+                    &[view_bindings.offset()],
+                );
+            }
+            render_pass.draw(0..3, 0..1);
+        }
+
+        if let Some(blend) = blend {
+            blend.render(
+                render_context,
+                world,
+                post_process.source,
+                post_process.destination,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether the multi pass effect `T` runs on a view.
+fn is_multi_pass_applied<T: MultiPassPostProcess>(world: &World, view: Entity) -> bool {
+    world.get_entity(view).is_ok_and(|view| {
+        view.contains::<ViewMultiPass<T>>() && !is_weightless(view.get::<EffectWeight<T>>())
+    })
+}
+
+/// Panics if the `passes` of the effect named `effect` don't fit its `targets`.
+fn validate_passes(effect: &str, targets: &[EffectTarget], passes: &[EffectPass]) {
+    assert!(
+        passes.last().is_some_and(|pass| pass.output.is_none()),
+        "The last pass of {effect} must render to the view"
+    );
+    for name in passes
+        .iter()
+        .flat_map(|pass| pass.inputs.iter().chain(&pass.output))
+    {
+        assert!(
+            targets.iter().any(|target| target.name == *name),
+            "{effect} has no target named {name}"
+        );
+    }
+    // Pooled targets are undefined until rendered to in a frame, persistent ones keep the
+    // previous frame.
+    let mut written = targets
+        .iter()
+        .filter(|target| target.persistent)
+        .map(|target| target.name)
+        .collect::<Vec<_>>();
+    for pass in passes {
+        assert!(
+            pass.output
+                .is_none_or(|output| !pass.inputs.contains(&output)),
+            "The pass {} of {effect} reads the target it renders to",
+            pass.entry_point
+        );
+        for input in &pass.inputs {
+            assert!(
+                written.contains(input),
+                "The pass {} of {effect} reads the target {input} before it is rendered to",
+                pass.entry_point
+            );
+        }
+        written.extend(pass.output);
+    }
+}
+
+/// Adds the multi pass effect `T`.
+pub struct MultiPassPostProcessPlugin<T: MultiPassPostProcess>(PhantomData<fn() -> T>);
+
+impl<T: MultiPassPostProcess> Default for MultiPassPostProcessPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: MultiPassPostProcess> Plugin for MultiPassPostProcessPlugin<T> {
+    fn build(&self, app: &mut App) {
+        validate_passes(type_name::<T>(), &T::targets(), &T::passes());
+
+        app.add_plugins((
+            ExtractComponentPlugin::<T>::default(),
+            ExtractComponentPlugin::<ViewMultiPassShaderDefs<T>>::default(),
+            UniformComponentPlugin::<T::Out>::default(),
+            ExtractResourcePlugin::<EffectShader<T>>::default(),
+        ));
+
+        if !app.is_plugin_added::<EffectBlendPlugin<T>>() {
+            app.add_plugins(EffectBlendPlugin::<T>::default());
+        }
+
+        if T::view_bindings() {
+            add_view_bindings(app);
+        }
+
+        init_effect_shader::<T>(app, T::shader_path());
+
+        register_effect_node(
+            app,
+            T::Label::default(),
+            |world| {
+                Box::new(ViewNodeRunner::new(
+                    MultiPassPostProcessNode::<T>::default(),
+                    world,
+                ))
+            },
+            is_multi_pass_applied::<T>,
+        );
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<SpecializedRenderPipelines<MultiPassPipeline<T>>>()
+            .init_resource::<MultiPassBindGroup<T>>()
+            .add_systems(
+                Render,
+                (
+                    prepare_multi_pass::<T>.in_set(RenderSet::PrepareResources),
+                    prepare_multi_pass_bind_group::<T>.in_set(RenderSet::PrepareBindGroups),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<MultiPassPipeline<T>>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blur_targets() -> Vec<EffectTarget> {
+        vec![EffectTarget::new("horizontal")]
+    }
+
+    #[test]
+    fn valid_passes() {
+        validate_passes(
+            "blur",
+            &blur_targets(),
+            &[
+                EffectPass::new("horizontal").writing("horizontal"),
+                EffectPass::new("vertical").reading("horizontal"),
+            ],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "blur has no target named vertical")]
+    fn unknown_target() {
+        validate_passes(
+            "blur",
+            &blur_targets(),
+            &[
+                EffectPass::new("horizontal").writing("horizontal"),
+                EffectPass::new("vertical").reading("vertical"),
+            ],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The pass horizontal of blur reads the target it renders to")]
+    fn pass_reading_its_output() {
+        validate_passes(
+            "blur",
+            &blur_targets(),
+            &[
+                EffectPass::new("horizontal")
+                    .reading("horizontal")
+                    .writing("horizontal"),
+                EffectPass::new("vertical").reading("horizontal"),
+            ],
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The pass vertical of blur reads the target horizontal before it is rendered to"
+    )]
+    fn target_read_before_written() {
+        validate_passes(
+            "blur",
+            &blur_targets(),
+            &[
+                EffectPass::new("vertical").reading("horizontal"),
+                EffectPass::new("horizontal").writing("horizontal"),
+                EffectPass::new("composite").reading("horizontal"),
+            ],
+        );
+    }
+
+    #[test]
+    fn persistent_target_read_before_written() {
+        validate_passes(
+            "feedback",
+            &[
+                EffectTarget::new("history").persistent(),
+                EffectTarget::new("accumulated"),
+            ],
+            &[
+                EffectPass::new("accumulate")
+                    .reading("history")
+                    .writing("accumulated"),
+                EffectPass::new("store")
+                    .reading("accumulated")
+                    .writing("history"),
+                EffectPass::new("present").reading("accumulated"),
+            ],
+        );
+    }
+}
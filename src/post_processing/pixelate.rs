@@ -5,14 +5,27 @@ use bevy::{
 
 use std::fmt::Display;
 
-use super::simple_post_process::SimplePostProcess;
+use super::{
+    fusion::{FusionStage, FusionStageKind},
+    impulse::ImpulseEffect,
+    shader::embedded_shader_path,
+    simple_post_process::SimplePostProcess,
+    tween::EffectLerp,
+};
 
 ///TODO
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
 pub struct PixelatePostProcessLabel;
 
 /// Pixelate settings.
-#[derive(Component, Clone, Copy, ExtractComponent, ShaderType)]
+#[derive(Component, Clone, Copy, ExtractComponent, ShaderType, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub struct Pixelate {
     /// How many pixels in the width and height in a block after pixelation. One block has a constant color within it.
     ///
@@ -26,6 +39,20 @@ impl Default for Pixelate {
     }
 }
 
+impl EffectLerp for Pixelate {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            block_size: self.block_size.lerp(other.block_size, t),
+        }
+    }
+}
+
+impl ImpulseEffect for Pixelate {
+    fn at_rest(&self) -> Self {
+        Self { block_size: 1.0 }
+    }
+}
+
 impl Display for Pixelate {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Pixelate block size: {}", self.block_size)
@@ -34,12 +61,17 @@ impl Display for Pixelate {
 
 impl SimplePostProcess for Pixelate {
     fn shader_path() -> String {
-        concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/assets/shaders/",
-            "pixelate.wgsl"
-        )
-        .into()
+        embedded_shader_path("pixelate.wgsl")
     }
     type Label = PixelatePostProcessLabel;
+
+    fn fusion_stage() -> Option<FusionStage> {
+        Some(FusionStage {
+            module: embedded_shader_path("stages/pixelate.wgsl"),
+            import_path: "bevy_vfx_bag::pixelate",
+            uniform: "Pixelate",
+            function: "pixelate_uv",
+            kind: FusionStageKind::Uv,
+        })
+    }
 }
@@ -2,32 +2,37 @@ use std::marker::PhantomData;
 
 use bevy::{
     asset::{Asset, Handle},
-    core_pipeline::{
-        core_3d::graph::Core3d, fullscreen_vertex_shader::fullscreen_shader_vertex_state,
-    },
     ecs::{
         query::QueryItem,
-        system::{
-            lifetimeless::{SRes, SResMut},
-            SystemParamItem,
-        },
+        system::{lifetimeless::SRes, SystemParamItem},
     },
     prelude::*,
     render::{
         extract_component::{ExtractComponent, ExtractComponentPlugin},
-        globals::{GlobalsBuffer, GlobalsUniform},
+        extract_resource::ExtractResourcePlugin,
         render_asset::{PrepareAssetError, RenderAsset, RenderAssetPlugin, RenderAssets},
-        render_graph::{
-            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
-        },
-        render_resource::{
-            binding_types::{sampler, texture_2d, uniform_buffer},
-            *,
-        },
+        render_graph::{NodeRunError, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner},
+        render_resource::*,
         renderer::{RenderContext, RenderDevice},
+        sync_world::MainEntity,
         view::ViewTarget,
-        RenderApp,
+        Render, RenderApp, RenderSet,
+    },
+};
+
+use super::{
+    blend::{is_weightless, EffectBlendPlugin, EffectWeight, ViewEffectBlend},
+    clock::globals_binding,
+    diagnostics::effect_name,
+    resolution::{EffectResolutionPlugin, ViewEffectResolution},
+    shader::{init_effect_shader, EffectShader},
+    simple_post_process::{
+        post_process_pipeline_descriptor, shared_layout, PostProcessPipelineKey,
+        ViewPostProcessPipeline,
     },
+    skipped::{report_skipped, SkipReason, SkippedEffects},
+    stack::register_effect,
+    view_bindings::{add_view_bindings, ViewBindings, ViewBindingsKey, ViewBindingsLayouts},
 };
 
 ///TODO
@@ -43,17 +48,19 @@ pub trait PostProcess: Component + Clone + ExtractComponent + Default // + Write
     fn handle(&self) -> Handle<Self::BindGroup>;
     ///TODO
     fn init(_app: &mut App) {}
+    /// Whether the effect reads the view's uniform and prepass textures, bound at group 2.
+    ///
+    /// See [`ViewBindingsKey`] for how shaders access them.
+    fn view_bindings() -> bool {
+        false
+    }
 }
 
 ///TODO
 pub struct PreparedPostProcessBindGroup<T: PostProcess> {
     _bindings: Vec<(u32, OwnedBindingResource)>,
     bind_group: BindGroup,
-    //key: <<T as PostProcess>::BindGroup as AsBindGroup>::Data,
-    pipeline_id: CachedRenderPipelineId,
-    layouts: Vec<BindGroupLayout>,
-    sampler: Sampler,
-    //pub properties: MaterialProperties,
+    shader_defs: Vec<ShaderDefVal>,
     _marker: PhantomData<T>,
 }
 
@@ -70,75 +77,23 @@ impl<T: PostProcess> RenderAsset for PreparedPostProcessBindGroup<T> {
 
     type Param = (
         SRes<RenderDevice>,
-        SResMut<PipelineCache>,
-        SRes<AssetServer>,
+        SRes<PostProcessPipeline<T>>,
         <<T as PostProcess>::BindGroup as AsBindGroup>::Param,
     );
 
     fn prepare_asset(
         bind_group_res: Self::SourceAsset,
-        (render_device, ref mut pipeline_cache, asset_server, ref mut param): &mut SystemParamItem<
-            Self::Param,
-        >,
+        (render_device, post_process_pipeline, ref mut param): &mut SystemParamItem<Self::Param>,
     ) -> Result<Self, PrepareAssetError<Self::SourceAsset>> {
         info!("Preparing post process bind group");
-        let bind_group_layout = Self::SourceAsset::bind_group_layout(render_device);
-        let shared_layout = render_device.create_bind_group_layout(
-            "post_process_bind_group_layout",
-            &BindGroupLayoutEntries::sequential(
-                // The layout entries will only be visible in the fragment stage
-                ShaderStages::FRAGMENT,
-                (
-                    // The screen texture
-                    texture_2d(TextureSampleType::Float { filterable: true }),
-                    // The sampler that will be used to sample the screen texture
-                    sampler(SamplerBindingType::Filtering),
-                    // The settings uniform that will control the effect
-                    uniform_buffer::<GlobalsUniform>(false),
-                ),
-            ),
-        );
-
-        match bind_group_res.as_bind_group(&bind_group_layout, render_device, param) {
-            Ok(prepared) => {
-                let layouts = vec![shared_layout, bind_group_layout];
-                let shader = asset_server.load(T::shader_path());
-                let pipeline_id = pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
-                    label: Some("post_process_pipeline".into()),
-                    layout: layouts.clone(),
-                    // This will setup a fullscreen triangle for the vertex state
-                    vertex: fullscreen_shader_vertex_state(),
-                    fragment: Some(FragmentState {
-                        shader,
-                        shader_defs: bind_group_res.shader_defs(),
-                        // Make sure this matches the entry point of your shader.
-                        // It can be anything as long as it matches here and in the shader.
-                        entry_point: "fragment".into(),
-                        targets: vec![Some(ColorTargetState {
-                            format: TextureFormat::bevy_default(),
-                            blend: None,
-                            write_mask: ColorWrites::ALL,
-                        })],
-                    }),
-                    // All of the following properties are not important for this effect so just use the default values.
-                    // This struct doesn't have the Default trait implemented because not all field can have a default value.
-                    primitive: PrimitiveState::default(),
-                    depth_stencil: None,
-                    multisample: MultisampleState::default(),
-                    push_constant_ranges: vec![],
-                    zero_initialize_workgroup_memory: false,
-                });
-                let sampler = render_device.create_sampler(&SamplerDescriptor::default());
-                Ok(Self {
-                    _bindings: prepared.bindings,
-                    bind_group: prepared.bind_group,
-                    //key: prepared.data,
-                    pipeline_id,
-                    layouts,
-                    sampler,
-                    _marker: PhantomData,
-                })
-            }
+        match bind_group_res.as_bind_group(&post_process_pipeline.layouts[1], render_device, param)
+        {
+            Ok(prepared) => Ok(Self {
+                _bindings: prepared.bindings,
+                bind_group: prepared.bind_group,
+                shader_defs: bind_group_res.shader_defs(),
+                _marker: PhantomData,
+            }),
             Err(AsBindGroupError::RetryNextUpdate) => {
                 Err(PrepareAssetError::RetryNextUpdate(bind_group_res))
             }
@@ -147,39 +102,165 @@ impl<T: PostProcess> RenderAsset for PreparedPostProcessBindGroup<T> {
     }
 }
 
+/// This contains global data used by the render pipeline. This will be created once on startup.
+///
+/// The actual render pipelines are specialized per view, see [`PostProcessPipelineKey`].
+#[derive(Resource)]
+pub struct PostProcessPipeline<T: PostProcess> {
+    layouts: Vec<BindGroupLayout>,
+    view_layouts: Option<ViewBindingsLayouts>,
+    sampler: Sampler,
+    _marker: PhantomData<T>,
+}
+
+impl<T: PostProcess> FromWorld for PostProcessPipeline<T> {
+    fn from_world(world: &mut World) -> Self {
+        let view_layouts =
+            T::view_bindings().then(|| world.get_resource_or_init::<ViewBindingsLayouts>().clone());
+        let render_device = world.resource::<RenderDevice>();
+
+        let layouts = vec![
+            shared_layout(render_device),
+            T::BindGroup::bind_group_layout(render_device),
+        ];
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+
+        Self {
+            layouts,
+            view_layouts,
+            sampler,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: PostProcess> SpecializedRenderPipeline for PostProcessPipeline<T> {
+    type Key = PostProcessPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        post_process_pipeline_descriptor(
+            effect_name(T::Label::default()),
+            self.layouts.clone(),
+            key,
+            self.view_layouts.as_ref(),
+        )
+    }
+}
+
+/// Reports views whose bind group is not prepared yet, e.g. while the LUT image loads.
+///
+/// These views get no pipeline, so the effect's node does not run on them.
+fn report_unprepared_bind_groups<T: PostProcess>(
+    skipped: Res<SkippedEffects>,
+    bind_groups: Res<RenderAssets<PreparedPostProcessBindGroup<T>>>,
+    views: Query<(Entity, &T, Option<&MainEntity>)>,
+) {
+    for (entity, component, main_entity) in &views {
+        if bind_groups.get(&component.handle()).is_none() {
+            skipped.report::<T>(
+                main_entity.map_or(entity, MainEntity::id),
+                T::Label::default(),
+                SkipReason::BindGroupUnavailable,
+            );
+        }
+    }
+}
+
+fn prepare_post_process_pipelines<T: PostProcess>(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<PostProcessPipeline<T>>>,
+    post_process_pipeline: Res<PostProcessPipeline<T>>,
+    shader: Res<EffectShader<T>>,
+    bind_groups: Res<RenderAssets<PreparedPostProcessBindGroup<T>>>,
+    views: Query<(Entity, &ViewTarget, &T, Option<&ViewBindingsKey>)>,
+) {
+    for (entity, view_target, component, view_bindings) in &views {
+        let Some(bind_group) = bind_groups.get(&component.handle()) else {
+            continue;
+        };
+
+        let id = pipelines.specialize(
+            &pipeline_cache,
+            &post_process_pipeline,
+            PostProcessPipelineKey {
+                shader: shader.handle.clone(),
+                texture_format: view_target.main_texture_format(),
+                shader_defs: bind_group.shader_defs.clone(),
+                view_bindings: T::view_bindings()
+                    .then(|| view_bindings.copied().unwrap_or_default()),
+            },
+        );
+
+        commands
+            .entity(entity)
+            .insert(ViewPostProcessPipeline::<T>::new(id));
+    }
+}
+
 ///TODO
 #[derive(Default)]
 pub struct PostProcessNode<T: PostProcess>(PhantomData<fn() -> T>);
 
 // The ViewNode trait is required by the ViewNodeRunner
 impl<T: PostProcess> ViewNode for PostProcessNode<T> {
-    type ViewQuery = (&'static ViewTarget, &'static T);
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static T,
+        &'static ViewPostProcessPipeline<T>,
+        Option<&'static EffectWeight<T>>,
+        Option<&'static ViewEffectBlend<T>>,
+        Option<&'static ViewEffectResolution<T>>,
+        Option<&'static ViewBindings>,
+    );
 
     fn run(
         &self,
-        _graph: &mut RenderGraphContext,
+        graph: &mut RenderGraphContext,
         render_context: &mut RenderContext,
-        (view_target, component): QueryItem<Self::ViewQuery>,
+        (view_target, component, view_pipeline, weight, blend, resolution, view_bindings): QueryItem<
+            Self::ViewQuery,
+        >,
         world: &World,
     ) -> Result<(), NodeRunError> {
+        if is_weightless(weight) {
+            return Ok(());
+        }
+
+        // The bind group is prepared once the effect's asset and its textures are loaded.
         let bind_group = world.resource::<RenderAssets<PreparedPostProcessBindGroup<T>>>();
-        let prepared_post_process_bind_group =
-            bind_group.get(&component.handle()).unwrap_or_else(|| {
-                panic!(
-                    "Failed to get prepared post process bind group for handle: {:?}",
-                    component.handle()
-                )
-            });
+        let Some(prepared_post_process_bind_group) = bind_group.get(&component.handle()) else {
+            report_skipped::<T>(
+                world,
+                graph.view_entity(),
+                T::Label::default(),
+                SkipReason::BindGroupUnavailable,
+            );
+            return Ok(());
+        };
+        let post_process_pipeline = world.resource::<PostProcessPipeline<T>>();
         let pipeline_cache = world.resource::<PipelineCache>();
 
         // Get the pipeline from the cache
-        let Some(pipeline) =
-            pipeline_cache.get_render_pipeline(prepared_post_process_bind_group.pipeline_id)
-        else {
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(view_pipeline.id) else {
             return Ok(());
         };
 
-        let Some(globals) = world.resource::<GlobalsBuffer>().buffer.binding() else {
+        // A partially applied effect is only shown once it can be blended with its input.
+        if blend.is_some_and(|blend| !blend.is_ready(world)) {
+            return Ok(());
+        }
+        // Likewise an effect at reduced resolution once it can be upsampled.
+        if resolution.is_some_and(|resolution| !resolution.is_ready(world)) {
+            return Ok(());
+        }
+
+        // Effects reading the view wait for its prepass textures.
+        if T::view_bindings() && view_bindings.is_none() {
+            return Ok(());
+        }
+
+        let Some(globals) = globals_binding(world, graph.view_entity()) else {
             return Ok(());
         };
 
@@ -201,23 +282,28 @@ impl<T: PostProcess> ViewNode for PostProcessNode<T> {
         // is to make sure you get it during the node execution.
         let shared_bind_group = render_context.render_device().create_bind_group(
             "post_process_bind_group",
-            &prepared_post_process_bind_group.layouts[0],
+            &post_process_pipeline.layouts[0],
             // It's important for this to match the BindGroupLayout defined in the PostProcessPipeline
             &BindGroupEntries::sequential((
                 // Make sure to use the source view
                 post_process.source,
                 // Use the sampler created for the pipeline
-                &prepared_post_process_bind_group.sampler,
+                &post_process_pipeline.sampler,
                 // Set the settings binding
                 globals,
             )),
         );
 
+        // At reduced resolution the effect renders into an intermediate texture
+        let target: &TextureView =
+            resolution.map_or(post_process.destination, ViewEffectResolution::target);
+
         // Begin the render pass
+        let label = effect_name(T::Label::default());
         let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
-            label: Some("post_process_pass"),
+            label: Some(&label),
             color_attachments: &[Some(RenderPassColorAttachment {
-                view: post_process.destination,
+                view: target,
                 resolve_target: None,
                 ops: Operations::default(),
             })],
@@ -231,7 +317,29 @@ impl<T: PostProcess> ViewNode for PostProcessNode<T> {
         render_pass.set_bind_group(0, &shared_bind_group, &[]);
 
         render_pass.set_bind_group(1, &prepared_post_process_bind_group.bind_group, &[]);
+        if let Some(view_bindings) = view_bindings.filter(|_| T::view_bindings()) {
+            render_pass.set_bind_group(2, view_bindings.bind_group(), &[view_bindings.offset()]);
+        }
         render_pass.draw(0..3, 0..1);
+        drop(render_pass);
+
+        if let Some(resolution) = resolution {
+            resolution.upsample(
+                render_context,
+                world,
+                post_process.source,
+                post_process.destination,
+            );
+        }
+
+        if let Some(blend) = blend {
+            blend.render(
+                render_context,
+                world,
+                post_process.source,
+                post_process.destination,
+            );
+        }
 
         Ok(())
     }
@@ -252,31 +360,89 @@ impl<T: PostProcess> Plugin for PostProcessPlugin<T> {
         app.init_asset::<T::BindGroup>().add_plugins((
             ExtractComponentPlugin::<T>::default(),
             RenderAssetPlugin::<PreparedPostProcessBindGroup<T>>::default(),
+            ExtractResourcePlugin::<EffectShader<T>>::default(),
         ));
 
+        if !app.is_plugin_added::<EffectBlendPlugin<T>>() {
+            app.add_plugins(EffectBlendPlugin::<T>::default());
+        }
+        if !app.is_plugin_added::<EffectResolutionPlugin<T>>() {
+            app.add_plugins(EffectResolutionPlugin::<T>::default());
+        }
+
+        if T::view_bindings() {
+            add_view_bindings(app);
+        }
+
+        init_effect_shader::<T>(app, T::shader_path());
+
+        register_effect::<T>(app, T::Label::default(), |world| {
+            Box::new(ViewNodeRunner::new(PostProcessNode::<T>::default(), world))
+        });
+
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
 
         render_app
-            // .add_systems(
-            //     Render,
-            //     post_process_shader_def_system::<T>.in_set(RenderSet::Queue),
-            // )
-            .add_render_graph_node::<ViewNodeRunner<PostProcessNode<T>>>(
-                Core3d,
-                T::Label::default(),
+            .init_resource::<SpecializedRenderPipelines<PostProcessPipeline<T>>>()
+            .add_systems(
+                Render,
+                (
+                    report_unprepared_bind_groups::<T>,
+                    prepare_post_process_pipelines::<T>,
+                )
+                    .in_set(RenderSet::Prepare),
             );
     }
 
-    // fn finish(&self, app: &mut App) {
-    // app.init_resource::<PostProcessImage<T>>()
-    //     .insert_resource(PostProcessShaderDef::<T>::default());
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<PostProcessPipeline<T>>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::RunSystemOnce,
+        render::{render_asset::RenderAssets, sync_world::MainEntity},
+    };
 
-    // let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
-    //     return;
-    // };
+    use super::*;
+    use crate::post_processing::{
+        lut::{Lut, LutPostProcessLabel},
+        skipped::{EffectSkipped, EffectSkippedPlugin, SkipReason, SkippedEffects},
+    };
 
-    // render_app.init_resource::<PostProcessPipeline<T>>();
-    // }
+    #[test]
+    fn unloaded_lut_is_reported() {
+        let mut app = App::new();
+        app.add_plugins(EffectSkippedPlugin);
+        let camera = app.world_mut().spawn_empty().id();
+
+        // A render world where the LUT's bind group is not prepared, as while its image loads.
+        let mut render_world = World::new();
+        render_world.insert_resource(app.world().resource::<SkippedEffects>().clone());
+        render_world.init_resource::<RenderAssets<PreparedPostProcessBindGroup<Lut>>>();
+        render_world.spawn((Lut::default(), MainEntity::from(camera)));
+        for _ in 0..2 {
+            render_world
+                .run_system_once(report_unprepared_bind_groups::<Lut>)
+                .expect("Should run report system");
+        }
+
+        app.update();
+        let events = app.world().resource::<Events<EffectSkipped>>();
+        let mut cursor = events.get_cursor();
+        let skipped = cursor.read(events).collect::<Vec<_>>();
+
+        assert_eq!(skipped.len(), 1, "Should report once per camera and reason");
+        assert_eq!(skipped[0].camera, camera);
+        assert_eq!(skipped[0].label, LutPostProcessLabel.intern());
+        assert_eq!(skipped[0].reason, SkipReason::BindGroupUnavailable);
+    }
 }
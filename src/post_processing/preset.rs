@@ -0,0 +1,312 @@
+use std::fmt;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+    render::render_graph::RenderLabel,
+    utils::HashSet,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    blur::{Blur, BlurPostProcessLabel},
+    chromatic_aberration::{ChromaticAberration, ChromaticAberrationPostProcessLabel},
+    flip::{Flip, FlipPostProcessLabel, FlipUniform},
+    lut::{Lut, LutPostProcessBindGroup, LutPostProcessLabel},
+    masks::{Mask, MaskPostProcessLabel},
+    pixelate::{Pixelate, PixelatePostProcessLabel},
+    raindrops::{Raindrops, RaindropsPostProcessLabel},
+    screen_transition::{Transition, TransitionPostProcessLabel},
+    stack::PostProcessingOrder,
+    wave::{Wave, WavePostProcessLabel},
+};
+
+/// A stack of effects and their order, usually loaded from a `.vfx.ron` file.
+///
+/// Apply it to a camera with a [`VfxStackHandle`]. Effects are applied in the order listed,
+/// each at most once. Fields left out use the effect's defaults:
+///
+/// ```ron
+/// (
+///     effects: [
+///         Wave(waves_x: 1.0, speed_x: 0.1, amplitude_x: 0.07),
+///         Lut(Arctic),
+///         Mask(variant: Vignette),
+///     ],
+/// )
+/// ```
+#[derive(Asset, TypePath, Clone, Default, Serialize, Deserialize)]
+pub struct VfxStack {
+    /// The effects, in the order they are applied.
+    pub effects: Vec<VfxEffect>,
+}
+
+/// An effect in a [`VfxStack`].
+#[derive(Clone, Serialize, Deserialize)]
+pub enum VfxEffect {
+    /// See [`Flip`].
+    Flip(Flip),
+    /// See [`Raindrops`].
+    Raindrops(Raindrops),
+    /// See [`Mask`].
+    Mask(Mask),
+    /// See [`Lut`].
+    Lut(LutPreset),
+    /// See [`ChromaticAberration`].
+    ChromaticAberration(ChromaticAberration),
+    /// See [`Blur`].
+    Blur(Blur),
+    /// See [`Wave`].
+    Wave(Wave),
+    /// See [`Pixelate`].
+    Pixelate(Pixelate),
+    /// See [`Transition`]. A dissolve's noise texture is not loaded from the file.
+    Transition(Transition),
+}
+
+/// The look-up textures shipped with this crate, see [`Lut`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LutPreset {
+    /// See [`Lut::arctic`].
+    Arctic,
+    /// See [`Lut::neo`].
+    Neo,
+    /// See [`Lut::slate`].
+    Slate,
+}
+
+impl VfxStack {
+    /// Replaces the effects on a camera with this stack's.
+    pub fn apply(&self, camera: &mut EntityCommands, luts: &mut Assets<LutPostProcessBindGroup>) {
+        remove_effects(camera);
+
+        let mut order = PostProcessingOrder::new();
+        for effect in &self.effects {
+            let label = effect.label();
+            if order.labels().contains(&label) {
+                warn!("{label:?} is listed more than once in a VFX stack, only the first is used");
+                continue;
+            }
+            order.push(label);
+            effect.insert(camera, luts);
+        }
+
+        camera.insert(order);
+    }
+}
+
+impl VfxEffect {
+    fn label(&self) -> bevy::render::render_graph::InternedRenderLabel {
+        match self {
+            VfxEffect::Flip(_) => FlipPostProcessLabel.intern(),
+            VfxEffect::Raindrops(_) => RaindropsPostProcessLabel.intern(),
+            VfxEffect::Mask(_) => MaskPostProcessLabel.intern(),
+            VfxEffect::Lut(_) => LutPostProcessLabel.intern(),
+            VfxEffect::ChromaticAberration(_) => ChromaticAberrationPostProcessLabel.intern(),
+            VfxEffect::Blur(_) => BlurPostProcessLabel.intern(),
+            VfxEffect::Wave(_) => WavePostProcessLabel.intern(),
+            VfxEffect::Pixelate(_) => PixelatePostProcessLabel.intern(),
+            VfxEffect::Transition(_) => TransitionPostProcessLabel.intern(),
+        }
+    }
+
+    fn insert(&self, camera: &mut EntityCommands, luts: &mut Assets<LutPostProcessBindGroup>) {
+        match self {
+            VfxEffect::Flip(flip) => {
+                camera.insert((*flip, FlipUniform::from(*flip)));
+            }
+            VfxEffect::Raindrops(raindrops) => {
+                camera.insert(*raindrops);
+            }
+            VfxEffect::Mask(mask) => {
+                camera.insert(*mask);
+            }
+            VfxEffect::Lut(preset) => {
+                camera.insert(match preset {
+                    LutPreset::Arctic => Lut::arctic(luts),
+                    LutPreset::Neo => Lut::neo(luts),
+                    LutPreset::Slate => Lut::slate(luts),
+                });
+            }
+            VfxEffect::ChromaticAberration(chromatic_aberration) => {
+                camera.insert(*chromatic_aberration);
+            }
+            VfxEffect::Blur(blur) => {
+                camera.insert(*blur);
+            }
+            VfxEffect::Wave(wave) => {
+                camera.insert(*wave);
+            }
+            VfxEffect::Pixelate(pixelate) => {
+                camera.insert(*pixelate);
+            }
+            VfxEffect::Transition(transition) => {
+                camera.insert(transition.clone());
+            }
+        }
+    }
+}
+
+/// Removes all effects a [`VfxStack`] may add from a camera.
+fn remove_effects(camera: &mut EntityCommands) {
+    camera.remove::<(
+        Flip,
+        FlipUniform,
+        Raindrops,
+        Mask,
+        Lut,
+        ChromaticAberration,
+        Blur,
+        Wave,
+        Pixelate,
+        Transition,
+        PostProcessingOrder,
+    )>();
+}
+
+/// Applies a [`VfxStack`] to a camera.
+///
+/// The stack owns the camera's effects: they are replaced whenever the stack's file changes,
+/// and removed with this component.
+#[derive(Debug, Clone, Default, Component)]
+pub struct VfxStackHandle(pub Handle<VfxStack>);
+
+fn apply_vfx_stacks(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<VfxStack>>,
+    mut removed: RemovedComponents<VfxStackHandle>,
+    stacks: Res<Assets<VfxStack>>,
+    mut luts: ResMut<Assets<LutPostProcessBindGroup>>,
+    cameras: Query<(Entity, Ref<VfxStackHandle>)>,
+) {
+    let changed = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    for (entity, handle) in &cameras {
+        if !handle.is_changed() && !changed.contains(&handle.0.id()) {
+            continue;
+        }
+        // Applied once loaded.
+        let Some(stack) = stacks.get(&handle.0) else {
+            continue;
+        };
+
+        stack.apply(&mut commands.entity(entity), &mut luts);
+    }
+
+    for entity in removed.read() {
+        if let Some(mut camera) = commands.get_entity(entity) {
+            remove_effects(&mut camera);
+        }
+    }
+}
+
+/// How `.vfx.ron` files are parsed. Effects may be written as `Lut(Arctic)` instead of `Lut((Arctic))`.
+fn ron_options() -> ron::Options {
+    ron::Options::default()
+        .with_default_extension(ron::extensions::Extensions::UNWRAP_VARIANT_NEWTYPES)
+}
+
+/// Loads [`VfxStack`]s from `.vfx.ron` files.
+#[derive(Default)]
+pub struct VfxStackLoader;
+
+impl AssetLoader for VfxStackLoader {
+    type Asset = VfxStack;
+    type Settings = ();
+    type Error = VfxStackLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<VfxStack, VfxStackLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let stack = ron_options().from_bytes(&bytes)?;
+        Ok(stack)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["vfx.ron"]
+    }
+}
+
+/// Why a [`VfxStack`] failed to load.
+#[derive(Debug)]
+pub enum VfxStackLoaderError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not a valid stack.
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for VfxStackLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VfxStackLoaderError::Io(error) => write!(f, "could not read VFX stack: {error}"),
+            VfxStackLoaderError::Ron(error) => write!(f, "invalid VFX stack: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for VfxStackLoaderError {}
+
+impl From<std::io::Error> for VfxStackLoaderError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for VfxStackLoaderError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::Ron(error)
+    }
+}
+
+/// Adds the [`VfxStack`] asset and applies stacks to cameras with a [`VfxStackHandle`].
+#[derive(Debug, Default)]
+pub struct VfxStackPlugin;
+
+impl Plugin for VfxStackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<VfxStack>()
+            .init_asset_loader::<VfxStackLoader>()
+            .add_systems(PostUpdate, apply_vfx_stacks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn underwater_preset_parses() {
+        let stack: VfxStack = ron_options()
+            .from_str(include_str!("../../assets/presets/underwater.vfx.ron"))
+            .expect("Should parse the underwater preset");
+
+        let labels = stack
+            .effects
+            .iter()
+            .map(VfxEffect::label)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            [
+                RaindropsPostProcessLabel.intern(),
+                ChromaticAberrationPostProcessLabel.intern(),
+                WavePostProcessLabel.intern(),
+                LutPostProcessLabel.intern(),
+                MaskPostProcessLabel.intern(),
+            ]
+        );
+    }
+}
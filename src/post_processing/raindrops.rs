@@ -11,9 +11,20 @@ use bevy::{
 };
 use binding_types::{sampler, texture_2d, uniform_buffer};
 
-use super::simple_post_process::{SimplePostProcess, TextureInputs};
+use super::{
+    shader::embedded_shader_path,
+    simple_post_process::{SimplePostProcess, TextureInputs},
+    tween::EffectLerp,
+};
 ///TODO
-#[derive(Component, Clone, Copy, ExtractComponent, ShaderType)]
+#[derive(Component, Clone, Copy, ExtractComponent, ShaderType, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub struct Raindrops {
     /// How quickly the raindrops animate.
     pub speed: f32,
@@ -34,6 +45,16 @@ impl Default for Raindrops {
         }
     }
 }
+
+impl EffectLerp for Raindrops {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            speed: self.speed.lerp(other.speed, t),
+            warping: self.warping.lerp(other.warping, t),
+            zoom: self.zoom.lerp(other.zoom, t),
+        }
+    }
+}
 use std::fmt::Display;
 impl Display for Raindrops {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -47,12 +68,7 @@ impl Display for Raindrops {
 
 impl SimplePostProcess for Raindrops {
     fn shader_path() -> String {
-        concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/assets/shaders/",
-            "raindrops.wgsl"
-        )
-        .into()
+        embedded_shader_path("raindrops.wgsl")
     }
     type Label = RaindropsPostProcessLabel;
     fn layout(device: &RenderDevice) -> BindGroupLayout {
@@ -76,55 +92,55 @@ impl SimplePostProcess for Raindrops {
         layout: &BindGroupLayout,
         buffer: BindingResource,
         textures: &TextureInputs,
-    ) -> BindGroup {
-        let texture = match textures {
-            TextureInputs::Single(texture) => texture,
-            _ => panic!("Expected a single texture for raindrops post processing"),
+    ) -> Option<BindGroup> {
+        // The effect is skipped until the raindrops texture is uploaded.
+        let TextureInputs::Single(texture) = textures else {
+            return None;
         };
+        let gpu_image = world.resource::<RenderAssets<GpuImage>>().get(texture)?;
 
-        let gpu_image = world
-            .resource::<RenderAssets<GpuImage>>()
-            .get(texture)
-            .unwrap_or_else(|| panic!("Expected a GpuImage for raindrops post processing"));
-        device.create_bind_group(
+        Some(device.create_bind_group(
             "flip_bind_group",
             layout,
             &BindGroupEntries::sequential((&gpu_image.texture_view, &gpu_image.sampler, buffer)),
-        )
+        ))
     }
 
     fn textures(world: &mut World) -> TextureInputs {
-        let raindrops_sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
-            label: Some("Raindrops Sampler".into()),
-            address_mode_u: ImageAddressMode::Repeat,
-            address_mode_v: ImageAddressMode::Repeat,
-            address_mode_w: ImageAddressMode::Repeat,
-            ..default()
-        });
-
-        let image = Image::from_buffer(
-            include_bytes!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/assets/textures/",
-                "raindrops.tga"
-            )),
-            ImageType::Extension("tga"),
-            CompressedImageFormats::NONE,
-            false,
-            raindrops_sampler,
-            RenderAssetUsages::RENDER_WORLD,
-        )
-        .expect("Should load raindrops successfully");
-
         let handle = world
             .get_resource_mut::<Assets<Image>>()
             .unwrap_or_else(|| panic!("Expected an Assets<Image> resource"))
-            .add(image);
+            .add(raindrops_image());
 
         TextureInputs::Single(handle)
     }
 }
 
+/// The raindrops texture, which repeats.
+pub(crate) fn raindrops_image() -> Image {
+    let raindrops_sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        label: Some("Raindrops Sampler".into()),
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        address_mode_w: ImageAddressMode::Repeat,
+        ..default()
+    });
+
+    Image::from_buffer(
+        include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/assets/textures/",
+            "raindrops.tga"
+        )),
+        ImageType::Extension("tga"),
+        CompressedImageFormats::NONE,
+        false,
+        raindrops_sampler,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+    .expect("Should load raindrops successfully")
+}
+
 ///TODO
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
 pub struct RaindropsPostProcessLabel;
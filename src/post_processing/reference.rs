@@ -0,0 +1,371 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    color::{ColorToComponents, ColorToPacked, LinearRgba, Srgba},
+    image::Image,
+    math::{UVec3, Vec2, Vec3, Vec3Swizzles, Vec4, Vec4Swizzles},
+};
+use image::{Rgba, RgbaImage};
+
+use super::{
+    blur::Blur,
+    chromatic_aberration::ChromaticAberration,
+    flip::{Flip, FlipUniform},
+    fusion::{fused_steps, FusedStep, FusionStage},
+    masks::{Mask, MaskVariant},
+    pixelate::Pixelate,
+    raindrops::{raindrops_image, Raindrops},
+    simple_post_process::SimplePostProcess,
+    wave::Wave,
+};
+
+/// An image as effect shaders see the view's texture: in linear color,
+/// sampled with the nearest texel and clamped at the edges.
+struct Texture {
+    width: u32,
+    height: u32,
+    texels: Vec<Vec4>,
+}
+
+impl Texture {
+    /// Decodes an sRGB image, like sampling an SDR camera's texture does.
+    fn from_srgb(image: &RgbaImage) -> Self {
+        let texels = image
+            .pixels()
+            .map(|pixel| LinearRgba::from(Srgba::from_u8_array(pixel.0)).to_vec4())
+            .collect();
+
+        Self {
+            width: image.width(),
+            height: image.height(),
+            texels,
+        }
+    }
+
+    /// Reads an image's texels as they are, like sampling a non-sRGB texture does.
+    fn from_raw(image: &Image) -> Self {
+        let texels = image.data.chunks_exact(4).map(unorm).collect();
+
+        Self {
+            width: image.width(),
+            height: image.height(),
+            texels,
+        }
+    }
+
+    /// Encodes the texture as an sRGB image, like writing to an SDR camera's texture does.
+    fn to_srgb(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.width, self.height, |x, y| {
+            let texel = self.texels[(y * self.width + x) as usize];
+            Rgba(Srgba::from(LinearRgba::from_vec4(texel)).to_u8_array())
+        })
+    }
+
+    fn resolution(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    fn load(&self, x: i64, y: i64) -> Vec4 {
+        let x = x.clamp(0, self.width as i64 - 1);
+        let y = y.clamp(0, self.height as i64 - 1);
+        self.texels[(y * self.width as i64 + x) as usize]
+    }
+
+    /// Samples like the sampler bound for the view's texture.
+    fn sample(&self, uv: Vec2) -> Vec4 {
+        let texel = (uv * self.resolution()).floor();
+        self.load(texel.x as i64, texel.y as i64)
+    }
+
+    /// Samples with the nearest texel, repeating the texture.
+    fn sample_repeat(&self, uv: Vec2) -> Vec4 {
+        let texel = (uv * self.resolution()).floor();
+        self.load(
+            (texel.x as i64).rem_euclid(self.width as i64),
+            (texel.y as i64).rem_euclid(self.height as i64),
+        )
+    }
+
+    /// Runs a fragment shader over each texel, given its UV.
+    fn fragment(&self, shader: impl Fn(Vec2) -> Vec4) -> Self {
+        let texels = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| shader((Vec2::new(x as f32, y as f32) + 0.5) / self.resolution()))
+            .collect();
+
+        Self {
+            width: self.width,
+            height: self.height,
+            texels,
+        }
+    }
+}
+
+/// Reads an 8 bit per channel RGBA texel.
+fn unorm(texel: &[u8]) -> Vec4 {
+    Vec4::new(
+        texel[0] as f32,
+        texel[1] as f32,
+        texel[2] as f32,
+        texel[3] as f32,
+    ) / 255.0
+}
+
+/// Runs an effect's fragment shader over an sRGB image.
+fn apply(image: &RgbaImage, shader: impl Fn(&Texture, Vec2) -> Vec4) -> RgbaImage {
+    let texture = Texture::from_srgb(image);
+    texture.fragment(|uv| shader(&texture, uv)).to_srgb()
+}
+
+/// [`Blur`] on the CPU.
+pub fn blur(image: &RgbaImage, blur: &Blur) -> RgbaImage {
+    apply(image, |t, uv| {
+        let s = |offset: Vec2| t.sample(uv + offset * blur.kernel_radius).xyz();
+
+        let blurred = [
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (1.0, 1.0),
+            (0.0, 1.0),
+            (-1.0, 1.0),
+            (-1.0, 0.0),
+            (-1.0, -1.0),
+            (0.0, -1.0),
+            (1.0, -1.0),
+        ]
+        .into_iter()
+        .map(|(x, y)| s(Vec2::new(x, y)))
+        .sum::<Vec3>()
+            / 9.0;
+
+        s(Vec2::ZERO).lerp(blurred, blur.amount).extend(1.0)
+    })
+}
+
+/// `pixelate_uv` of `stages/pixelate.wgsl`.
+fn pixelate_uv(uv: Vec2, resolution: Vec2, pixelate: &Pixelate) -> Vec2 {
+    let blocks = resolution / pixelate.block_size.max(1.0);
+    ((uv + 0.5) * blocks).floor() / blocks - 0.5
+}
+
+/// [`Pixelate`] on the CPU.
+pub fn pixelate(image: &RgbaImage, pixelate: &Pixelate) -> RgbaImage {
+    apply(image, |t, uv| {
+        t.sample(pixelate_uv(uv, t.resolution(), pixelate))
+    })
+}
+
+/// `flip_uv` of `stages/flip.wgsl`.
+fn flip_uv(uv: Vec2, flip: &Flip) -> Vec2 {
+    let flip = FlipUniform::from(*flip);
+    (Vec2::new(flip.x, flip.y) - uv).abs()
+}
+
+/// [`Flip`] on the CPU.
+pub fn flip(image: &RgbaImage, flip: &Flip) -> RgbaImage {
+    apply(image, |t, uv| t.sample(flip_uv(uv, flip)))
+}
+
+/// `mask_color` of `stages/masks.wgsl`.
+fn mask_color(color: Vec4, uv: Vec2, mask: &Mask) -> Vec4 {
+    let result = match mask.variant {
+        MaskVariant::Square => {
+            let square = (uv * mask.strength).clamp(Vec2::ZERO, Vec2::ONE)
+                * ((1.0 - uv) * mask.strength).clamp(Vec2::ZERO, Vec2::ONE);
+            square.x * square.y
+        }
+        MaskVariant::Crt => {
+            let square = uv * (1.0 - uv);
+            let norm = square.x * square.y;
+            (norm * norm * mask.strength).clamp(0.0, 1.0)
+        }
+        MaskVariant::Vignette => {
+            let uv_centered = (uv * 2.0 - 1.0) * mask.strength;
+            let dist = uv_centered.length().clamp(0.0, 1.0).powf(1.5);
+            (1.0 - dist + 0.05).clamp(0.0, 1.0)
+        }
+    };
+
+    (color.xyz() * (result + mask.fade).clamp(0.0, 1.0)).extend(1.0)
+}
+
+/// [`Mask`] on the CPU.
+pub fn mask(image: &RgbaImage, mask: &Mask) -> RgbaImage {
+    apply(image, |t, uv| mask_color(t.sample(uv), uv, mask))
+}
+
+/// [`super::lut::Lut`] on the CPU.
+///
+/// The look-up texture is e.g. from [`super::lut::Lut::image_from_png`],
+/// sampled with linear filtering like Bevy's default sampler does.
+pub fn lut(image: &RgbaImage, lut: &Image) -> RgbaImage {
+    let size = lut.texture_descriptor.size;
+    let size = UVec3::new(size.width, size.height, size.depth_or_array_layers);
+    let load = |texel: UVec3| {
+        let texel = texel.min(size - 1);
+        let index = ((texel.z * size.y + texel.y) * size.x + texel.x) as usize * 4;
+        unorm(&lut.data[index..index + 4])
+    };
+    let sample = |uvw: Vec3| {
+        let position = (uvw * size.as_vec3() - 0.5).max(Vec3::ZERO);
+        let base = position.floor();
+        let fraction = position - base;
+        let base = base.as_uvec3();
+
+        let mut color = Vec4::ZERO;
+        for corner in 0..8u32 {
+            let offset = UVec3::new(corner & 1, (corner >> 1) & 1, (corner >> 2) & 1);
+            let weight = Vec3::select(offset.as_vec3().cmpeq(Vec3::ONE), fraction, 1.0 - fraction);
+            color += load(base + offset) * weight.x * weight.y * weight.z;
+        }
+        color
+    };
+
+    apply(image, |t, uv| {
+        let half_texel = Vec3::splat(1.0 / 64.0 / 2.0);
+        // The shader samples with ".rbg", see `lut.wgsl`.
+        let raw_color = t.sample(uv).xzy();
+
+        sample(raw_color + half_texel).xyz().extend(1.0)
+    })
+}
+
+/// [`ChromaticAberration`] on the CPU.
+pub fn chromatic_aberration(image: &RgbaImage, ca: &ChromaticAberration) -> RgbaImage {
+    apply(image, |t, uv| {
+        Vec4::new(
+            t.sample(uv + ca.dir_r * ca.magnitude_r).x,
+            t.sample(uv + ca.dir_g * ca.magnitude_g).y,
+            t.sample(uv + ca.dir_b * ca.magnitude_b).z,
+            1.0,
+        )
+    })
+}
+
+/// `wave_uv` of `stages/wave.wgsl`.
+fn wave_uv(uv: Vec2, time: f32, wave: &Wave) -> Vec2 {
+    let pi_uv = PI * uv;
+    let pi_time = PI * time;
+
+    let offset_x = ((pi_uv.y * wave.waves_x) + (pi_time * wave.speed_x)).sin() * wave.amplitude_x;
+    let offset_y = ((pi_uv.x * wave.waves_y) + (pi_time * wave.speed_y)).sin() * wave.amplitude_y;
+
+    uv + Vec2::new(offset_x, offset_y)
+}
+
+/// [`Wave`] on the CPU at the given time in seconds.
+pub fn wave(image: &RgbaImage, wave: &Wave, time: f32) -> RgbaImage {
+    apply(image, |t, uv| t.sample(wave_uv(uv, time, wave)))
+}
+
+/// An effect rendered as part of a fused pass, see [`fused`].
+#[derive(Clone)]
+pub enum FusedStage {
+    /// A [`Flip`] UV stage.
+    Flip(Flip),
+    /// A [`Wave`] UV stage.
+    Wave(Wave),
+    /// A [`Pixelate`] UV stage.
+    Pixelate(Pixelate),
+    /// A [`Mask`] color stage.
+    Mask(Mask),
+}
+
+impl FusedStage {
+    /// The effect's stage as the stack fuses it.
+    fn fusion_stage(&self) -> FusionStage {
+        match self {
+            FusedStage::Flip(_) => FlipUniform::fusion_stage(),
+            FusedStage::Wave(_) => Wave::fusion_stage(),
+            FusedStage::Pixelate(_) => Pixelate::fusion_stage(),
+            FusedStage::Mask(_) => Mask::fusion_stage(),
+        }
+        .expect("Should be a fusable effect")
+    }
+
+    /// The stage function for a UV stage, `stages/*.wgsl` on the CPU.
+    fn uv(&self, uv: Vec2, resolution: Vec2, time: f32) -> Vec2 {
+        match self {
+            FusedStage::Flip(flip) => flip_uv(uv, flip),
+            FusedStage::Wave(wave) => wave_uv(uv, time, wave),
+            FusedStage::Pixelate(pixelate) => pixelate_uv(uv, resolution, pixelate),
+            FusedStage::Mask(_) => uv,
+        }
+    }
+
+    /// The stage function for a color stage, `stages/*.wgsl` on the CPU.
+    fn color(&self, color: Vec4, uv: Vec2) -> Vec4 {
+        match self {
+            FusedStage::Mask(mask) => mask_color(color, uv, mask),
+            _ => color,
+        }
+    }
+}
+
+/// [`super::fusion::PostProcessFusion`] on the CPU at the given time in seconds.
+///
+/// Runs the same steps as the shader generated for the effects' fusion stages,
+/// so it can be compared to rendering them one by one.
+pub fn fused(image: &RgbaImage, stages: &[FusedStage], time: f32) -> RgbaImage {
+    let kinds = stages
+        .iter()
+        .map(|stage| stage.fusion_stage().kind)
+        .collect::<Vec<_>>();
+    let steps = fused_steps(&kinds);
+
+    apply(image, |t, out_uv| {
+        let mut uv = out_uv;
+        let mut kept_uvs = vec![Vec2::ZERO; stages.len()];
+        let mut color = Vec4::ZERO;
+        for step in &steps {
+            match *step {
+                FusedStep::Uv(i) => {
+                    uv = stages[i]
+                        .uv(uv, t.resolution(), time)
+                        .clamp(Vec2::ZERO, Vec2::ONE);
+                }
+                FusedStep::KeepUv(i) => kept_uvs[i] = uv,
+                FusedStep::Sample => color = t.sample(uv),
+                FusedStep::Color(i) => color = stages[i].color(color, kept_uvs[i]),
+            }
+        }
+        color
+    })
+}
+
+/// [`Raindrops`] on the CPU at the given time in seconds.
+pub fn raindrops(image: &RgbaImage, raindrops: &Raindrops, time: f32) -> RgbaImage {
+    raindrops_with_texture(image, raindrops, time, &raindrops_image())
+}
+
+/// [`Raindrops`] on the CPU with another raindrops texture, which should repeat.
+///
+/// The texture is read like the shipped one: red and green are the warp direction,
+/// blue the animation phase and alpha whether a drop is animated or static.
+pub fn raindrops_with_texture(
+    image: &RgbaImage,
+    raindrops: &Raindrops,
+    time: f32,
+    texture: &Image,
+) -> RgbaImage {
+    let rain = Texture::from_raw(texture);
+
+    apply(image, |t, uv| {
+        let resolution = t.resolution();
+        let uv_aspect_fixed = Vec2::new(uv.x * resolution.x / resolution.y, uv.y);
+
+        let t_raindrops = rain.sample_repeat(uv_aspect_fixed * raindrops.zoom);
+        // These channels should be (-1, 1) but come as (0, 1).
+        let t_raindrops_rga = Vec3::new(t_raindrops.x, t_raindrops.y, t_raindrops.w) * 2.0 - 1.0;
+
+        let mask_anim = t_raindrops_rga.z.clamp(0.0, 1.0);
+        let mask_static = (-t_raindrops_rga.z).clamp(0.0, 1.0);
+
+        let offset = t_raindrops_rga.xy() * raindrops.warping;
+        let animation = (t_raindrops.z - time * raindrops.speed).rem_euclid(1.0);
+
+        let mask = animation * mask_anim + mask_static;
+
+        t.sample(uv + mask * offset).xyz().extend(1.0)
+    })
+}
@@ -0,0 +1,343 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_resource::{
+            binding_types::{sampler, texture_2d},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::{CachedTexture, TextureCache},
+        view::ViewTarget,
+        Render, RenderApp, RenderSet,
+    },
+};
+
+use super::shader::embedded_shader_path;
+
+/// Renders the effect `T` at a reduced resolution on a camera.
+///
+/// The effect renders into a smaller texture, which is then upsampled back to the
+/// camera's resolution. This makes expensive effects like [`super::blur::Blur`] cheaper,
+/// especially on high DPI displays.
+///
+/// Effects without this component render at full resolution.
+///
+/// ```rust,ignore
+/// commands.spawn((
+///     Camera3d::default(),
+///     Blur::default(),
+///     EffectResolution::<Blur>::half().with_upsampling(Upsampling::EdgeAware),
+/// ));
+/// ```
+#[derive(Debug, Component)]
+pub struct EffectResolution<T> {
+    /// The resolution relative to the camera's, from `0.0` to `1.0`.
+    pub scale: f32,
+    /// How the effect's output is scaled back up.
+    pub upsampling: Upsampling,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> EffectResolution<T> {
+    /// Renders the effect at the given scale of the camera's resolution.
+    pub fn new(scale: f32) -> Self {
+        Self {
+            scale,
+            upsampling: Upsampling::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Renders the effect at half the camera's resolution.
+    pub fn half() -> Self {
+        Self::new(0.5)
+    }
+
+    /// Renders the effect at a quarter of the camera's resolution.
+    pub fn quarter() -> Self {
+        Self::new(0.25)
+    }
+
+    /// Scales the effect's output back up the given way.
+    pub fn with_upsampling(mut self, upsampling: Upsampling) -> Self {
+        self.upsampling = upsampling;
+        self
+    }
+}
+
+impl<T> Default for EffectResolution<T> {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl<T> Clone for EffectResolution<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for EffectResolution<T> {}
+
+impl<T: 'static> ExtractComponent for EffectResolution<T> {
+    type QueryData = &'static Self;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(resolution: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        Some(*resolution)
+    }
+}
+
+/// How an effect rendered at reduced resolution is scaled back up.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Upsampling {
+    /// Interpolates between the nearest pixels. Cheap, but blurs edges.
+    #[default]
+    Bilinear,
+    /// Like bilinear, but guided by the effect's full resolution input so edges stay sharp.
+    EdgeAware,
+}
+
+impl Upsampling {
+    /// The shader defs of the upsampling shader.
+    pub(crate) fn shader_defs(self) -> Vec<ShaderDefVal> {
+        match self {
+            Upsampling::Bilinear => vec![],
+            Upsampling::EdgeAware => vec!["EDGE_AWARE".into()],
+        }
+    }
+}
+
+/// The pipeline scaling an effect's output back up to the view's resolution.
+#[derive(Resource)]
+pub(crate) struct EffectUpsamplePipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    shader: Handle<Shader>,
+}
+
+impl FromWorld for EffectUpsamplePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "effect_upsample_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    // The effect's output at reduced resolution
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    // The effect's input, guiding edge aware upsampling
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..default()
+        });
+        let shader = world
+            .resource::<AssetServer>()
+            .load(embedded_shader_path("upsample.wgsl"));
+
+        Self {
+            layout,
+            sampler,
+            shader,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for EffectUpsamplePipeline {
+    type Key = (TextureFormat, Upsampling);
+
+    fn specialize(&self, (texture_format, upsampling): Self::Key) -> RenderPipelineDescriptor {
+        let shader_defs = upsampling.shader_defs();
+
+        RenderPipelineDescriptor {
+            label: Some("effect_upsample_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                shader_defs,
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: texture_format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+/// The reduced resolution texture the effect `T` renders into on a specific view.
+///
+/// Only present on views where the effect renders at reduced resolution.
+#[derive(Component)]
+pub struct ViewEffectResolution<T> {
+    texture: CachedTexture,
+    pipeline: CachedRenderPipelineId,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ViewEffectResolution<T> {
+    /// Whether the upsample pipeline is compiled.
+    pub(crate) fn is_ready(&self, world: &World) -> bool {
+        world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(self.pipeline)
+            .is_some()
+    }
+
+    /// Where the effect renders to.
+    pub(crate) fn target(&self) -> &TextureView {
+        &self.texture.default_view
+    }
+
+    /// Scales the effect's output up into `destination`.
+    ///
+    /// `source` is the effect's input, used to guide edge aware upsampling.
+    pub(crate) fn upsample(
+        &self,
+        render_context: &mut RenderContext,
+        world: &World,
+        source: &TextureView,
+        destination: &TextureView,
+    ) {
+        let upsample_pipeline = world.resource::<EffectUpsamplePipeline>();
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(self.pipeline)
+        else {
+            return;
+        };
+
+        let bind_group = render_context.render_device().create_bind_group(
+            "effect_upsample_bind_group",
+            &upsample_pipeline.layout,
+            &BindGroupEntries::sequential((
+                &self.texture.default_view,
+                &upsample_pipeline.sampler,
+                source,
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("effect_upsample_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+fn prepare_effect_resolution<T: 'static>(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    mut texture_cache: ResMut<TextureCache>,
+    pipeline_cache: Res<PipelineCache>,
+    upsample_pipeline: Res<EffectUpsamplePipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<EffectUpsamplePipeline>>,
+    views: Query<(Entity, &ViewTarget, &EffectResolution<T>)>,
+) {
+    for (entity, view_target, resolution) in &views {
+        // At full resolution the effect renders into the view target directly.
+        if resolution.scale >= 1.0 {
+            commands.entity(entity).remove::<ViewEffectResolution<T>>();
+            continue;
+        }
+
+        let size = view_target.main_texture().size();
+        let scale = resolution.scale.max(0.0);
+        let texture_format = view_target.main_texture_format();
+
+        let texture = texture_cache.get(
+            &render_device,
+            TextureDescriptor {
+                label: Some("effect_low_resolution_texture"),
+                size: Extent3d {
+                    width: ((size.width as f32 * scale) as u32).max(1),
+                    height: ((size.height as f32 * scale) as u32).max(1),
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: texture_format,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+        let pipeline = pipelines.specialize(
+            &pipeline_cache,
+            &upsample_pipeline,
+            (texture_format, resolution.upsampling),
+        );
+
+        commands.entity(entity).insert(ViewEffectResolution::<T> {
+            texture,
+            pipeline,
+            _marker: PhantomData,
+        });
+    }
+}
+
+/// Adds [`EffectResolution`] support for the effect `T`.
+///
+/// Added by the effect plugins, so it does not need to be added manually.
+pub(crate) struct EffectResolutionPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for EffectResolutionPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: 'static> Plugin for EffectResolutionPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ExtractComponentPlugin::<EffectResolution<T>>::default());
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<SpecializedRenderPipelines<EffectUpsamplePipeline>>()
+            .add_systems(
+                Render,
+                prepare_effect_resolution::<T>.in_set(RenderSet::PrepareResources),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<EffectUpsamplePipeline>();
+    }
+}
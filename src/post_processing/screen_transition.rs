@@ -0,0 +1,383 @@
+use bevy::{
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::ExtractComponent,
+        render_asset::RenderAssets,
+        render_graph::RenderLabel,
+        render_resource::*,
+        renderer::RenderDevice,
+        texture::{FallbackImage, GpuImage},
+    },
+};
+use binding_types::{sampler, texture_2d, uniform_buffer};
+
+use super::{
+    shader::embedded_shader_path,
+    simple_post_process::{PostProcessImage, SimplePostProcess, TextureInputs},
+    tween::EffectLerp,
+};
+
+///TODO
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
+pub struct TransitionPostProcessLabel;
+
+/// A screen transition, covering the image with a color as [`Self::progress`] goes from 0 to 1.
+///
+/// Animate the progress, e.g. with an [`super::tween::EffectTween`]:
+///
+/// ```rust,ignore
+/// commands.entity(camera).insert((
+///     Transition::iris(Vec2::splat(0.5)),
+///     EffectTween::new(Transition::iris(Vec2::splat(0.5)))
+///         .then(Transition::iris(Vec2::splat(0.5)).with_progress(1.0), 1.0, EaseFunction::CubicIn),
+/// ));
+/// ```
+#[derive(Debug, Component, Clone, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub struct Transition {
+    /// How far the transition is: 0.0 shows the image, 1.0 covers it with the color.
+    pub progress: f32,
+
+    /// How the color covers the image.
+    pub mode: TransitionMode,
+
+    /// The color covering the image. Its alpha makes the cover see-through.
+    pub color: LinearRgba,
+
+    /// How soft the edge between the image and the color is, in progress units.
+    /// Not used by [`TransitionMode::Fade`].
+    pub softness: f32,
+
+    /// A glow in front of the edge, if any. Not used by [`TransitionMode::Fade`].
+    pub edge_glow: Option<EdgeGlow>,
+}
+
+/// How a [`Transition`] covers the image.
+#[derive(Debug, Default, Clone, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub enum TransitionMode {
+    /// Fade the whole image to the color.
+    #[default]
+    Fade,
+
+    /// Sweep the color across the image.
+    Wipe {
+        /// The direction the edge moves in, in UV space, e.g. `Vec2::X` to wipe from left to right.
+        direction: Vec2,
+    },
+
+    /// Close a circle around a point.
+    Iris {
+        /// The point the circle closes on, in UV space.
+        center: Vec2,
+    },
+
+    /// Grow shapes in a grid of tiles until they fill the image.
+    Tiles {
+        /// The shape growing in each tile.
+        shape: TileShape,
+        /// The width and height of a tile, in pixels.
+        size: f32,
+    },
+
+    /// Cover the image where a noise texture is below the progress first.
+    ///
+    /// The noise texture is shared by all cameras, the last camera set up with a different one wins.
+    Dissolve {
+        /// The noise texture, read from its red channel. It should repeat.
+        ///
+        /// Asset handles can not be serialized, so this is left out and deserialized as the
+        /// default handle, which covers the image all at once at the end. Set it after loading.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        noise: Handle<Image>,
+        /// How often the noise repeats over the height of the image.
+        scale: f32,
+    },
+}
+
+/// The shape growing in each tile of a [`TransitionMode::Tiles`] transition.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub enum TileShape {
+    /// A square rotated by 45 degrees.
+    #[default]
+    Diamond,
+    /// A circle.
+    Circle,
+}
+
+/// A colored glow in front of the edge of a [`Transition`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub struct EdgeGlow {
+    /// The glow's color. Its alpha is the glow's strength.
+    pub color: LinearRgba,
+    /// How far the glow reaches, in progress units.
+    pub width: f32,
+}
+
+impl Default for EdgeGlow {
+    fn default() -> Self {
+        Self {
+            color: LinearRgba::rgb(1.0, 0.6, 0.2),
+            width: 0.05,
+        }
+    }
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self {
+            progress: 0.0,
+            mode: TransitionMode::default(),
+            color: LinearRgba::BLACK,
+            softness: 0.02,
+            edge_glow: None,
+        }
+    }
+}
+
+impl Transition {
+    /// Fade to black.
+    pub fn fade() -> Self {
+        Self::default()
+    }
+
+    /// Wipe in the given direction.
+    pub fn wipe(direction: Vec2) -> Self {
+        Self {
+            mode: TransitionMode::Wipe { direction },
+            ..default()
+        }
+    }
+
+    /// Close an iris on the given point in UV space.
+    pub fn iris(center: Vec2) -> Self {
+        Self {
+            mode: TransitionMode::Iris { center },
+            ..default()
+        }
+    }
+
+    /// Grow the given shape in tiles of the given size in pixels.
+    pub fn tiles(shape: TileShape, size: f32) -> Self {
+        Self {
+            mode: TransitionMode::Tiles { shape, size },
+            ..default()
+        }
+    }
+
+    /// Dissolve the image driven by the given noise texture.
+    pub fn dissolve(noise: Handle<Image>) -> Self {
+        Self {
+            mode: TransitionMode::Dissolve { noise, scale: 1.0 },
+            ..default()
+        }
+    }
+
+    /// Sets the progress.
+    pub fn with_progress(mut self, progress: f32) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Covers the image with the given color.
+    pub fn with_color(mut self, color: impl Into<LinearRgba>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Sets the softness of the edge.
+    pub fn with_softness(mut self, softness: f32) -> Self {
+        self.softness = softness;
+        self
+    }
+
+    /// Adds a glow in front of the edge.
+    pub fn with_edge_glow(mut self, color: impl Into<LinearRgba>, width: f32) -> Self {
+        self.edge_glow = Some(EdgeGlow {
+            color: color.into(),
+            width,
+        });
+        self
+    }
+}
+
+impl EffectLerp for Transition {
+    /// The mode can not be interpolated, so it switches to `other`'s once `t` reaches 1.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let edge_glow = match (self.edge_glow, other.edge_glow) {
+            (Some(from), Some(to)) => Some(EdgeGlow {
+                color: from.color.mix(&to.color, t),
+                width: from.width.lerp(to.width, t),
+            }),
+            (from, to) => {
+                if t < 1.0 {
+                    from
+                } else {
+                    to
+                }
+            }
+        };
+
+        Self {
+            progress: self.progress.lerp(other.progress, t),
+            mode: if t < 1.0 {
+                self.mode.clone()
+            } else {
+                other.mode.clone()
+            },
+            color: self.color.mix(&other.color, t),
+            softness: self.softness.lerp(other.softness, t),
+            edge_glow,
+        }
+    }
+
+    fn can_lerp(&self, other: &Self) -> bool {
+        self.mode == other.mode
+    }
+}
+
+/// The part of [`Transition`] passed to the shader.
+/// The mode is passed as a shader definition instead.
+#[derive(Component, Clone, Copy, ShaderType)]
+#[cfg_attr(test, derive(Reflect))]
+pub struct TransitionUniform {
+    color: Vec4,
+    edge_color: Vec4,
+    direction: Vec2,
+    center: Vec2,
+    progress: f32,
+    softness: f32,
+    edge_width: f32,
+    tile_size: f32,
+    noise_scale: f32,
+}
+
+impl ExtractComponent for Transition {
+    type QueryData = &'static Self;
+    type QueryFilter = ();
+    type Out = TransitionUniform;
+
+    fn extract_component(transition: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        let (direction, center, tile_size, noise_scale) = match &transition.mode {
+            TransitionMode::Fade => (Vec2::X, Vec2::ZERO, 1.0, 1.0),
+            TransitionMode::Wipe { direction } => (
+                direction.try_normalize().unwrap_or(Vec2::X),
+                Vec2::ZERO,
+                1.0,
+                1.0,
+            ),
+            TransitionMode::Iris { center } => (Vec2::X, *center, 1.0, 1.0),
+            TransitionMode::Tiles { size, .. } => (Vec2::X, Vec2::ZERO, size.max(1.0), 1.0),
+            TransitionMode::Dissolve { scale, .. } => (Vec2::X, Vec2::ZERO, 1.0, *scale),
+        };
+        let edge_glow = transition.edge_glow.unwrap_or(EdgeGlow {
+            width: 0.0,
+            ..default()
+        });
+
+        Some(TransitionUniform {
+            color: transition.color.to_vec4(),
+            edge_color: edge_glow.color.to_vec4(),
+            direction,
+            center,
+            progress: transition.progress.clamp(0.0, 1.0),
+            softness: transition.softness.max(0.0),
+            edge_width: edge_glow.width.max(0.0),
+            tile_size,
+            noise_scale,
+        })
+    }
+}
+
+impl SimplePostProcess for Transition {
+    fn shader_path() -> String {
+        embedded_shader_path("transition.wgsl")
+    }
+    type Label = TransitionPostProcessLabel;
+    fn layout(device: &RenderDevice) -> BindGroupLayout {
+        device.create_bind_group_layout(
+            "transition_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    // The noise texture
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<TransitionUniform>(true),
+                ),
+            ),
+        )
+    }
+    fn bind_group(
+        world: &World,
+        device: &RenderDevice,
+        layout: &BindGroupLayout,
+        buffer: BindingResource,
+        textures: &TextureInputs,
+    ) -> Option<BindGroup> {
+        // Until the noise texture is loaded, dissolving covers the image all at once at the end.
+        let fallback = &world.resource::<FallbackImage>().d2;
+        let noise = textures
+            .handles()
+            .first()
+            .and_then(|handle| world.resource::<RenderAssets<GpuImage>>().get(handle))
+            .unwrap_or(fallback);
+
+        Some(device.create_bind_group(
+            "transition_bind_group",
+            layout,
+            &BindGroupEntries::sequential((&noise.texture_view, &noise.sampler, buffer)),
+        ))
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        match &self.mode {
+            TransitionMode::Fade => vec!["FADE".into()],
+            TransitionMode::Wipe { .. } => vec!["WIPE".into()],
+            TransitionMode::Iris { .. } => vec!["IRIS".into()],
+            TransitionMode::Tiles {
+                shape: TileShape::Diamond,
+                ..
+            } => vec!["TILES".into(), "TILE_DIAMOND".into()],
+            TransitionMode::Tiles {
+                shape: TileShape::Circle,
+                ..
+            } => vec!["TILES".into()],
+            TransitionMode::Dissolve { .. } => vec!["DISSOLVE".into()],
+        }
+    }
+}
+
+/// Binds the noise texture of the cameras dissolving.
+pub(crate) fn update_transition_noise(
+    mut image: ResMut<PostProcessImage<Transition>>,
+    transitions: Query<&Transition, Changed<Transition>>,
+) {
+    for transition in &transitions {
+        let TransitionMode::Dissolve { noise, .. } = &transition.mode else {
+            continue;
+        };
+        if image.texture_inputs.handles().first() != Some(noise) {
+            image.texture_inputs = TextureInputs::Single(noise.clone());
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::{marker::PhantomData, path::Path};
+
+use bevy::{
+    asset::io::embedded::EmbeddedAssetRegistry, prelude::*,
+    render::extract_resource::ExtractResource,
+};
+
+/// The shader an effect is rendered with.
+///
+/// By default this is the shader shipped with the crate, see [`embedded_shader_path`].
+/// Insert this resource to render an effect with another shader instead,
+/// for example one from your own assets folder which may then be hot reloaded:
+///
+/// ```rust,ignore
+/// fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     commands.insert_resource(EffectShader::<Blur>::new(
+///         asset_server.load("shaders/my_blur.wgsl"),
+///     ));
+/// }
+/// ```
+///
+/// The shader must use the same bindings as the one it replaces.
+#[derive(Resource, ExtractResource)]
+pub struct EffectShader<T: 'static> {
+    /// The shader to use.
+    pub handle: Handle<Shader>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> EffectShader<T> {
+    /// Use the given shader for the effect `T`.
+    pub fn new(handle: Handle<Shader>) -> Self {
+        Self {
+            handle,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static> Clone for EffectShader<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.handle.clone())
+    }
+}
+
+/// Loads the given shader as the [`EffectShader`] of `T`, unless one was inserted already.
+pub(crate) fn init_effect_shader<T: 'static>(app: &mut App, path: String) {
+    if app.world().contains_resource::<EffectShader<T>>() {
+        return;
+    }
+
+    let handle = app.world().resource::<AssetServer>().load(path);
+    app.insert_resource(EffectShader::<T>::new(handle));
+}
+
+/// Shader modules imported by effect shaders.
+///
+/// Imports are resolved from loaded shaders, so these are kept loaded.
+#[derive(Resource, Default)]
+struct ShaderModules {
+    handles: Vec<Handle<Shader>>,
+}
+
+/// Loads a shader module and keeps it loaded.
+pub(crate) fn load_shader_module(app: &mut App, path: String) {
+    let handle = app.world().resource::<AssetServer>().load(path);
+    app.world_mut()
+        .get_resource_or_init::<ShaderModules>()
+        .handles
+        .push(handle);
+}
+
+/// The asset path of a shader shipped with this crate.
+///
+/// The file name is relative to the `assets/shaders` folder of this crate.
+pub fn embedded_shader_path(file_name: &str) -> String {
+    format!("embedded://bevy_vfx_bag/shaders/{file_name}")
+}
+
+/// Embeds shaders from the `assets/shaders` folder of this crate into the binary.
+///
+/// This means shaders are found regardless of where the application is run from.
+pub(crate) fn embed_shaders(app: &mut App) {
+    macro_rules! embed {
+        ($($file_name: literal),* $(,)?) => {
+            let embedded = app.world().resource::<EmbeddedAssetRegistry>();
+            $(
+                embedded.insert_asset(
+                    Path::new(env!("CARGO_MANIFEST_DIR"))
+                        .join("assets/shaders")
+                        .join($file_name),
+                    &Path::new("bevy_vfx_bag/shaders").join($file_name),
+                    include_bytes!(concat!("../../assets/shaders/", $file_name)),
+                );
+            )*
+        };
+    }
+
+    embed!(
+        "blend.wgsl",
+        "blur.wgsl",
+        "chromatic-aberration.wgsl",
+        "compare.wgsl",
+        "flip.wgsl",
+        "lut.wgsl",
+        "masks.wgsl",
+        "pixelate.wgsl",
+        "post_processing.wgsl",
+        "raindrops.wgsl",
+        "transition.wgsl",
+        "upsample.wgsl",
+        "view_bindings.wgsl",
+        "wave.wgsl",
+        "stages/flip.wgsl",
+        "stages/masks.wgsl",
+        "stages/pixelate.wgsl",
+        "stages/wave.wgsl",
+    );
+}
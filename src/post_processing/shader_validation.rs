@@ -0,0 +1,514 @@
+use std::{fs, path::Path};
+
+use bevy::{
+    core_pipeline::CorePipelinePlugin,
+    prelude::*,
+    reflect::{TypeInfo, Typed},
+    render::{
+        render_resource::{
+            encase::{private::StructMetadata, ShaderType},
+            Shader, ShaderDefVal, ShaderImport, Source,
+        },
+        settings::WgpuSettings,
+        RenderPlugin,
+    },
+    utils::HashMap,
+};
+use naga::{
+    valid::{Capabilities, ValidationFlags, Validator},
+    AddressSpace, Module, ResourceBinding, Scalar, ScalarKind, TypeInner,
+};
+use naga_oil::compose::{
+    ComposableModuleDescriptor, Composer, NagaModuleDescriptor, ShaderDefValue, ShaderLanguage,
+    ShaderType as ComposerShaderType,
+};
+
+use super::{
+    blend::{blend_shader_defs, EffectBlendUniform, RegionShape},
+    blur::Blur,
+    chromatic_aberration::ChromaticAberration,
+    clock::EffectClockUniform,
+    comparison::EffectComparisonUniform,
+    compute::workgroup_size_shader_defs,
+    flip::FlipUniform,
+    fusion::fused_shader_source,
+    masks::Mask,
+    pixelate::Pixelate,
+    raindrops::Raindrops,
+    resolution::Upsampling,
+    screen_transition::{TileShape, Transition},
+    simple_post_process::SimplePostProcess,
+    test::TestPostProcessSettings,
+    view_bindings::ViewBindingsKey,
+    wave::Wave,
+};
+
+const SHADERS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders");
+
+/// Composes shaders with their imports like Bevy's pipeline cache does, then validates them.
+struct ShaderComposer {
+    composer: Composer,
+    /// Importable modules by import path: Bevy's and this crate's.
+    modules: HashMap<String, Shader>,
+}
+
+impl ShaderComposer {
+    fn new() -> Self {
+        // Bevy's shader modules are embedded and loaded by its plugins, which run without a GPU.
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            },
+            ImagePlugin::default(),
+            CorePipelinePlugin,
+        ));
+        // Some modules, e.g. `bevy_render::maths`, are only loaded once plugins are finished.
+        app.finish();
+        app.cleanup();
+
+        let mut modules = HashMap::default();
+        let crate_modules = wgsl_files(Path::new(SHADERS))
+            .into_iter()
+            .map(|(path, source)| Shader::from_wgsl(source, path));
+        let bevy_modules = app
+            .world()
+            .resource::<Assets<Shader>>()
+            .iter()
+            .map(|(_, shader)| shader.clone())
+            .collect::<Vec<_>>();
+        for shader in bevy_modules.into_iter().chain(crate_modules) {
+            if let ShaderImport::Custom(import_path) = &shader.import_path {
+                modules.insert(import_path.clone(), shader);
+            }
+        }
+
+        Self {
+            composer: Composer::default(),
+            modules,
+        }
+    }
+
+    /// Adds an imported module to the composer, after the modules it imports in turn.
+    fn add_import(&mut self, import: &ShaderImport) -> Result<(), String> {
+        let ShaderImport::Custom(import_path) = import else {
+            return Err(format!("unsupported import {import:?}"));
+        };
+        if self.composer.contains_module(import_path) {
+            return Ok(());
+        }
+        let Some(shader) = self.modules.get(import_path).cloned() else {
+            return Err(format!("no module `{import_path}`"));
+        };
+
+        for import in &shader.imports {
+            self.add_import(import)?;
+        }
+        let Source::Wgsl(source) = &shader.source else {
+            return Err(format!("module `{import_path}` is not WGSL"));
+        };
+        self.composer
+            .add_composable_module(ComposableModuleDescriptor {
+                source,
+                file_path: &shader.path,
+                language: ShaderLanguage::Wgsl,
+                shader_defs: shader_def_values(&shader.shader_defs),
+                ..default()
+            })
+            .map(|_| ())
+            .map_err(|error| error.emit_to_string(&self.composer))
+    }
+
+    /// Composes and validates a shader for the given shader defs.
+    fn validate(
+        &mut self,
+        path: &str,
+        source: &str,
+        shader_defs: &[ShaderDefVal],
+    ) -> Result<Module, String> {
+        let shader = Shader::from_wgsl(source.to_owned(), path.to_owned());
+        for import in &shader.imports {
+            self.add_import(import)?;
+        }
+
+        let module = self
+            .composer
+            .make_naga_module(NagaModuleDescriptor {
+                source,
+                file_path: path,
+                shader_type: ComposerShaderType::Wgsl,
+                shader_defs: shader_def_values(shader_defs),
+                ..default()
+            })
+            .map_err(|error| error.emit_to_string(&self.composer))?;
+
+        Validator::new(ValidationFlags::all(), Capabilities::default())
+            .validate(&module)
+            .map_err(|error| error.emit_to_string(source))?;
+
+        Ok(module)
+    }
+}
+
+fn shader_def_values(
+    shader_defs: &[ShaderDefVal],
+) -> std::collections::HashMap<String, ShaderDefValue> {
+    shader_defs
+        .iter()
+        .map(|shader_def| match shader_def {
+            ShaderDefVal::Bool(key, value) => (key.clone(), ShaderDefValue::Bool(*value)),
+            ShaderDefVal::Int(key, value) => (key.clone(), ShaderDefValue::Int(*value)),
+            ShaderDefVal::UInt(key, value) => (key.clone(), ShaderDefValue::UInt(*value)),
+        })
+        .collect()
+}
+
+/// The WGSL files in a folder and its subfolders, by path relative to [`SHADERS`].
+fn wgsl_files(folder: &Path) -> Vec<(String, String)> {
+    let mut files = vec![];
+    for entry in fs::read_dir(folder).expect("Should read shader folder") {
+        let path = entry.expect("Should read shader folder entry").path();
+        if path.is_dir() {
+            files.extend(wgsl_files(&path));
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "wgsl")
+        {
+            let relative = path
+                .strip_prefix(SHADERS)
+                .expect("Should be in the shader folder")
+                .to_string_lossy()
+                .replace('\\', "/");
+            let source = fs::read_to_string(&path).expect("Should read shader");
+            files.push((relative, source));
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Every set of shader defs the code using a shader specializes it with.
+fn shader_def_permutations(path: &str, source: &str) -> Vec<Vec<ShaderDefVal>> {
+    let permutations = match path {
+        "masks.wgsl" => [Mask::square(), Mask::crt(), Mask::vignette()]
+            .iter()
+            .map(SimplePostProcess::shader_defs)
+            .collect(),
+        "transition.wgsl" => [
+            Transition::fade(),
+            Transition::wipe(Vec2::X),
+            Transition::iris(Vec2::splat(0.5)),
+            Transition::tiles(TileShape::Diamond, 32.0),
+            Transition::tiles(TileShape::Circle, 32.0),
+            Transition::dissolve(Handle::default()),
+        ]
+        .iter()
+        .map(SimplePostProcess::shader_defs)
+        .collect(),
+        "blend.wgsl" => [
+            RegionShape::Rect {
+                min: Vec2::ZERO,
+                max: Vec2::ONE,
+            },
+            RegionShape::Circle {
+                center: Vec2::ZERO,
+                radius: 1.0,
+            },
+            RegionShape::Ellipse {
+                center: Vec2::ZERO,
+                radii: Vec2::ONE,
+            },
+            RegionShape::Mask(Handle::default()),
+        ]
+        .iter()
+        .map(|shape| blend_shader_defs(Some(shape.shader_def())))
+        .chain([blend_shader_defs(None)])
+        .collect(),
+        "upsample.wgsl" => [Upsampling::Bilinear, Upsampling::EdgeAware]
+            .into_iter()
+            .map(Upsampling::shader_defs)
+            .collect(),
+        // The compute example keeps the default workgroup size.
+        "examples/kuwahara.wgsl" => vec![workgroup_size_shader_defs(UVec2::splat(8)).to_vec()],
+        _ => vec![vec![]],
+    };
+
+    // Effects reading the view are specialized on the camera's prepasses,
+    // with the view bound after the input and the effect's own bind groups.
+    if source.contains("bevy_vfx_bag::view_bindings") {
+        return permutations
+            .iter()
+            .flat_map(|shader_defs| {
+                ViewBindingsKey::all().map(|key| [shader_defs.clone(), key.shader_defs(2)].concat())
+            })
+            .collect();
+    }
+
+    permutations
+}
+
+fn format_shader_defs(shader_defs: &[ShaderDefVal]) -> String {
+    shader_defs
+        .iter()
+        .map(|shader_def| match shader_def {
+            ShaderDefVal::Bool(key, true) => key.clone(),
+            ShaderDefVal::Bool(key, false) => format!("!{key}"),
+            ShaderDefVal::Int(key, value) => format!("{key}={value}"),
+            ShaderDefVal::UInt(key, value) => format!("{key}={value}"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[test]
+fn shaders_validate_for_all_shader_defs() {
+    let mut composer = ShaderComposer::new();
+    let mut failures = vec![];
+    let mut validated = 0;
+
+    for (path, source) in wgsl_files(Path::new(SHADERS)) {
+        // Modules are validated as part of the shaders importing them.
+        if source.contains("#define_import_path") {
+            continue;
+        }
+
+        for shader_defs in shader_def_permutations(&path, &source) {
+            validated += 1;
+            if let Err(error) = composer.validate(&path, &source, &shader_defs) {
+                failures.push(format!(
+                    "{path} [{}]:\n{error}",
+                    format_shader_defs(&shader_defs)
+                ));
+            }
+        }
+    }
+
+    assert!(validated > 0, "No shaders found in {SHADERS}");
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
+
+#[test]
+fn fused_shaders_validate() {
+    let mut composer = ShaderComposer::new();
+    let stages = [
+        FlipUniform::fusion_stage(),
+        Wave::fusion_stage(),
+        Pixelate::fusion_stage(),
+        Mask::fusion_stage(),
+    ]
+    .into_iter()
+    .map(|stage| stage.expect("Should be fusable"))
+    .collect::<Vec<_>>();
+    let source = fused_shader_source(&stages.iter().collect::<Vec<_>>());
+
+    for mask in [Mask::square(), Mask::crt(), Mask::vignette()] {
+        let shader_defs = mask.shader_defs();
+        if let Err(error) = composer.validate("fused.wgsl", &source, &shader_defs) {
+            panic!(
+                "fused.wgsl [{}]:\n{error}\n\n{source}",
+                format_shader_defs(&shader_defs)
+            );
+        }
+    }
+}
+
+/// The members of a uniform struct in order, as name, byte offset and WGSL type.
+type StructLayout = Vec<(String, u32, String)>;
+
+/// The layout and size of the uniform struct bound at the given group and binding of a shader.
+fn wgsl_uniform_layout(module: &Module, group: u32, binding: u32) -> Option<(StructLayout, u32)> {
+    let (_, variable) = module.global_variables.iter().find(|(_, variable)| {
+        variable.space == AddressSpace::Uniform
+            && variable.binding == Some(ResourceBinding { group, binding })
+    })?;
+    let TypeInner::Struct { members, span } = &module.types[variable.ty].inner else {
+        return None;
+    };
+
+    let layout = members
+        .iter()
+        .map(|member| {
+            let ty = match module.types[member.ty].inner {
+                TypeInner::Scalar(scalar) => scalar_name(scalar),
+                TypeInner::Vector { size, scalar } => {
+                    format!("vec{}<{}>", size as u8, scalar_name(scalar))
+                }
+                ref inner => format!("{inner:?}"),
+            };
+            (member.name.clone().unwrap_or_default(), member.offset, ty)
+        })
+        .collect();
+    Some((layout, *span))
+}
+
+fn scalar_name(scalar: Scalar) -> String {
+    let kind = match scalar.kind {
+        ScalarKind::Float => "f",
+        ScalarKind::Sint => "i",
+        ScalarKind::Uint => "u",
+        kind => return format!("{kind:?}"),
+    };
+    format!("{kind}{}", u32::from(scalar.width) * 8)
+}
+
+/// The offsets of a Rust uniform's fields, from its [`ShaderType`] metadata.
+trait FieldOffsets {
+    fn offsets(&self) -> Vec<u64>;
+}
+
+impl<const N: usize> FieldOffsets for StructMetadata<N> {
+    fn offsets(&self) -> Vec<u64> {
+        self.offsets.to_vec()
+    }
+}
+
+/// The layout of a Rust uniform, with field names and types from its [`Reflect`] derive.
+fn rust_uniform_layout<U>() -> StructLayout
+where
+    U: ShaderType<ExtraMetadata: FieldOffsets> + Typed,
+{
+    let TypeInfo::Struct(info) = U::type_info() else {
+        panic!("`{}` should be a struct", std::any::type_name::<U>());
+    };
+
+    info.iter()
+        .zip(U::METADATA.extra.offsets())
+        .map(|(field, offset)| {
+            let ty = match field.type_path_table().short_path() {
+                "Vec2" => "vec2<f32>",
+                "Vec3" => "vec3<f32>",
+                "Vec4" => "vec4<f32>",
+                "UVec2" => "vec2<u32>",
+                ty => ty,
+            };
+            (field.name().to_owned(), offset as u32, ty.to_owned())
+        })
+        .collect()
+}
+
+/// Checks a Rust uniform has the layout of the WGSL struct it is bound to:
+/// the same members, in the same order, at the same offsets.
+///
+/// A mismatch in size is a validation error when the pipeline is created, since bindings are
+/// sized by the Rust type. Any other mismatch silently passes the wrong values.
+fn check_uniform<U>(
+    composer: &mut ShaderComposer,
+    path: &str,
+    shader_defs: &[ShaderDefVal],
+    group: u32,
+    binding: u32,
+) -> Result<(), String>
+where
+    U: ShaderType<ExtraMetadata: FieldOffsets> + Typed,
+{
+    check_uniform_layout::<U>(composer, path, shader_defs, group, binding, false)
+}
+
+/// Checks an [`EffectClockUniform`] can be bound as the globals of a shader.
+///
+/// It is bound in place of Bevy's `Globals`, which is only padded to 16 bytes on WebGL2,
+/// so it may have a trailing padding member the WGSL struct does not.
+fn check_globals(
+    composer: &mut ShaderComposer,
+    path: &str,
+    shader_defs: &[ShaderDefVal],
+) -> Result<(), String> {
+    check_uniform_layout::<EffectClockUniform>(composer, path, shader_defs, 0, 2, true)
+}
+
+fn check_uniform_layout<U>(
+    composer: &mut ShaderComposer,
+    path: &str,
+    shader_defs: &[ShaderDefVal],
+    group: u32,
+    binding: u32,
+    padded: bool,
+) -> Result<(), String>
+where
+    U: ShaderType<ExtraMetadata: FieldOffsets> + Typed,
+{
+    let source = fs::read_to_string(Path::new(SHADERS).join(path)).expect("Should read shader");
+    let module = composer.validate(path, &source, shader_defs)?;
+    let Some((wgsl, wgsl_size)) = wgsl_uniform_layout(&module, group, binding) else {
+        return Err(format!(
+            "{path} has no uniform struct at group {group}, binding {binding}"
+        ));
+    };
+    let rust = rust_uniform_layout::<U>();
+    let rust_size = U::min_size().get();
+    let rust_name = std::any::type_name::<U>();
+
+    let fits = match padded {
+        true => rust_size >= u64::from(wgsl_size) && rust.len() >= wgsl.len(),
+        false => rust_size == u64::from(wgsl_size) && rust.len() == wgsl.len(),
+    };
+    if !fits {
+        return Err(format!(
+            "{path} group {group}, binding {binding} has {} members in {wgsl_size} bytes, \
+             but `{rust_name}` has {} in {rust_size} bytes",
+            wgsl.len(),
+            rust.len()
+        ));
+    }
+
+    let mismatch = wgsl.iter().zip(&rust).find(|(wgsl, rust)| wgsl != rust);
+    if let Some(((name, offset, ty), (field, field_offset, field_ty))) = mismatch {
+        return Err(format!(
+            "{path} group {group}, binding {binding} has `{name}: {ty}` at byte {offset}, \
+             but `{rust_name}` has `{field}: {field_ty}` at byte {field_offset}"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks an effect's uniform, and the globals it is given, against its shader.
+fn check_effect<T>(composer: &mut ShaderComposer, effect: T, binding: u32) -> Result<(), String>
+where
+    T: SimplePostProcess<Out: ShaderType<ExtraMetadata: FieldOffsets> + Typed>,
+{
+    let path = T::shader_path()
+        .strip_prefix("embedded://bevy_vfx_bag/shaders/")
+        .expect("Should be shipped with the crate")
+        .to_owned();
+    let shader_defs = effect.shader_defs();
+
+    check_uniform::<T::Out>(composer, &path, &shader_defs, 1, binding)?;
+    // Cameras with an `EffectClock` bind it in place of Bevy's globals.
+    check_globals(composer, &path, &shader_defs)
+}
+
+#[test]
+fn uniforms_match_shader_structs() {
+    let mut composer = ShaderComposer::new();
+    let composer = &mut composer;
+
+    let results = [
+        check_effect(composer, Blur::default(), 0),
+        check_effect(composer, ChromaticAberration::default(), 0),
+        check_effect(composer, FlipUniform::default(), 0),
+        check_effect(composer, Mask::square(), 0),
+        check_effect(composer, Mask::crt(), 0),
+        check_effect(composer, Mask::vignette(), 0),
+        check_effect(composer, Pixelate::default(), 0),
+        check_effect(composer, Raindrops::default(), 2),
+        check_effect(composer, Transition::default(), 2),
+        check_effect(composer, TestPostProcessSettings::default(), 0),
+        check_effect(composer, Wave::default(), 0),
+        check_globals(composer, "lut.wgsl", &[]),
+        check_uniform::<EffectBlendUniform>(composer, "blend.wgsl", &[], 0, 2),
+        check_uniform::<EffectComparisonUniform>(composer, "compare.wgsl", &[], 0, 2),
+    ];
+
+    let failures = results
+        .into_iter()
+        .filter_map(Result::err)
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -1,9 +1,7 @@
 use std::marker::PhantomData;
 
 use bevy::{
-    core_pipeline::{
-        core_3d::graph::Core3d, fullscreen_vertex_shader::fullscreen_shader_vertex_state,
-    },
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
     ecs::query::QueryItem,
     prelude::*,
     render::{
@@ -12,23 +10,38 @@ use bevy::{
             UniformComponentPlugin,
         },
         extract_resource::{ExtractResource, ExtractResourcePlugin},
-        globals::{GlobalsBuffer, GlobalsUniform},
-        render_graph::{
-            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
-        },
+        globals::GlobalsUniform,
+        render_asset::RenderAssets,
+        render_graph::{NodeRunError, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner},
         render_resource::{
             binding_types::{sampler, texture_2d, uniform_buffer},
             *,
         },
         renderer::{RenderContext, RenderDevice},
+        texture::GpuImage,
         view::ViewTarget,
         Render, RenderApp, RenderSet,
     },
 };
 use encase::internal::WriteInto;
+
+use super::{
+    blend::{is_weightless, EffectBlendPlugin, EffectWeight, ViewEffectBlend},
+    clock::globals_binding,
+    diagnostics::effect_name,
+    fusion::{FusableEffect, FusionStage},
+    resolution::{EffectResolutionPlugin, ViewEffectResolution},
+    shader::{init_effect_shader, load_shader_module, EffectShader},
+    skipped::{report_skipped, SkipReason},
+    stack::{register_effect, PostProcessEffects},
+    view_bindings::{add_view_bindings, ViewBindings, ViewBindingsKey, ViewBindingsLayouts},
+};
 ///This trait is used to define a post-processing effect.
+///
+///The effect's extracted component ([`ExtractComponent::Out`]) is the uniform passed to the shader.
+///For most effects this is the effect itself.
 pub trait SimplePostProcess:
-    Component + Clone + ExtractComponent + Default + WriteInto + ShaderType
+    Component + Clone + Default + ExtractComponent<Out: Component + Clone + WriteInto + ShaderType>
 {
     ///The label used to identify the post-processing effect.
     type Label: RenderLabel + Default;
@@ -40,19 +53,22 @@ pub trait SimplePostProcess:
             None,
             &BindGroupLayoutEntries::sequential(
                 ShaderStages::FRAGMENT,
-                (uniform_buffer::<Self>(true),),
+                (uniform_buffer::<Self::Out>(true),),
             ),
         )
     }
     ///The bind group used to pass data to the shader.
+    ///
+    ///It is shared by all views and only recreated when the uniform buffer or the textures change.
+    ///Returning `None`, e.g. while a texture is loading, skips the effect until then.
     fn bind_group(
         _world: &World,
         device: &RenderDevice,
         layout: &BindGroupLayout,
         buffer: BindingResource,
         _textures: &TextureInputs,
-    ) -> BindGroup {
-        device.create_bind_group(None, layout, &BindGroupEntries::sequential((buffer,)))
+    ) -> Option<BindGroup> {
+        Some(device.create_bind_group(None, layout, &BindGroupEntries::sequential((buffer,))))
     }
 
     ///if you want overwrite this function,maybe you should use trait: PostProcess instead.
@@ -61,103 +77,284 @@ pub trait SimplePostProcess:
     }
 
     ///The shader definitions used for the post-processing effect.
-    fn shader_defs() -> Vec<ShaderDefVal> {
+    ///
+    ///These are read per camera, so cameras may use different shader variants of the same effect.
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
         vec![]
     }
+
+    ///Whether the effect reads the view's uniform and prepass textures, bound at group 2.
+    ///
+    ///See [`ViewBindingsKey`] for how shaders access them.
+    fn view_bindings() -> bool {
+        false
+    }
+
+    ///How the effect is rendered together with adjacent effects, see [`super::fusion::PostProcessFusion`].
+    ///
+    ///Only effects which transform UVs or colors of single samples, without textures of their own,
+    ///can be fused.
+    fn fusion_stage() -> Option<FusionStage> {
+        None
+    }
 }
 
 /// This contains global data used by the render pipeline. This will be created once on startup.
+///
+/// The actual render pipelines are specialized per view, see [`PostProcessPipelineKey`].
 #[derive(Resource)]
 pub struct PostProcessPipeline<T: SimplePostProcess> {
     layouts: Vec<BindGroupLayout>,
+    view_layouts: Option<ViewBindingsLayouts>,
     sampler: Sampler,
-    //textures: TextureInputs,
-    pub(crate) pipeline_id: CachedRenderPipelineId,
     _marker: PhantomData<T>,
 }
 
-///TODO
-#[derive(Resource, ExtractResource, Clone)]
-pub struct PostProcessImage<T: SimplePostProcess> {
-    ///TODO
-    pub texture_inputs: TextureInputs,
-    _marker: PhantomData<T>,
+/// What a post processing render pipeline is specialized on.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct PostProcessPipelineKey {
+    /// The effect's shader, see [`EffectShader`].
+    pub shader: Handle<Shader>,
+    /// The format of the view's main texture, which differs between HDR and SDR cameras.
+    pub texture_format: TextureFormat,
+    /// The shader definitions used when compiling the shader.
+    pub shader_defs: Vec<ShaderDefVal>,
+    /// The view's prepass textures, if the effect reads the view.
+    pub view_bindings: Option<ViewBindingsKey>,
 }
 
-///TODO
-#[derive(Resource, ExtractResource, Clone)]
-pub struct PostProcessShaderDef<T: SimplePostProcess> {
-    shaderdefs: Vec<ShaderDefVal>,
-    changed: bool,
-    _marker: PhantomData<T>,
+/// The render pipeline an effect uses on a specific view.
+#[derive(Component)]
+pub struct ViewPostProcessPipeline<T> {
+    /// The id of the specialized pipeline.
+    pub id: CachedRenderPipelineId,
+    _marker: PhantomData<fn() -> T>,
 }
 
-impl<T: SimplePostProcess> Default for PostProcessShaderDef<T> {
-    fn default() -> Self {
+impl<T> ViewPostProcessPipeline<T> {
+    pub(crate) fn new(id: CachedRenderPipelineId) -> Self {
         Self {
-            shaderdefs: T::shader_defs(),
-            changed: false,
+            id,
             _marker: PhantomData,
         }
     }
 }
 
-impl<T: SimplePostProcess> PostProcessShaderDef<T> {
-    ///TODO
-    pub fn set_shader_defs(&mut self, shaderdefs: Vec<ShaderDefVal>) {
-        self.shaderdefs = shaderdefs;
-        self.changed = true;
+/// The bind group layout shared by all effects.
+///
+/// It contains the screen texture, a sampler for it, and the globals uniform.
+pub(crate) fn shared_layout(render_device: &RenderDevice) -> BindGroupLayout {
+    render_device.create_bind_group_layout(
+        "post_process_bind_group_layout",
+        &BindGroupLayoutEntries::sequential(
+            // The layout entries will only be visible in the fragment stage
+            ShaderStages::FRAGMENT,
+            (
+                // The screen texture
+                texture_2d(TextureSampleType::Float { filterable: true }),
+                // The sampler that will be used to sample the screen texture
+                sampler(SamplerBindingType::Filtering),
+                // The settings uniform that will control the effect
+                uniform_buffer::<GlobalsUniform>(false),
+            ),
+        ),
+    )
+}
+
+/// Describes a fullscreen post processing render pipeline, labelled with the name of its effect.
+///
+/// If the key has view bindings, these are bound after the given layouts.
+pub(crate) fn post_process_pipeline_descriptor(
+    label: String,
+    mut layouts: Vec<BindGroupLayout>,
+    mut key: PostProcessPipelineKey,
+    view_layouts: Option<&ViewBindingsLayouts>,
+) -> RenderPipelineDescriptor {
+    if let (Some(view_layouts), Some(view_key)) = (view_layouts, key.view_bindings) {
+        view_layouts.specialize(view_key, &mut layouts, &mut key.shader_defs);
     }
 
-    ///TODO
-    pub fn shader_defs(&self) -> &Vec<ShaderDefVal> {
-        &self.shaderdefs
+    RenderPipelineDescriptor {
+        label: Some(label.into()),
+        layout: layouts,
+        // This will setup a fullscreen triangle for the vertex state
+        vertex: fullscreen_shader_vertex_state(),
+        fragment: Some(FragmentState {
+            shader: key.shader,
+            shader_defs: key.shader_defs,
+            // Make sure this matches the entry point of your shader.
+            // It can be anything as long as it matches here and in the shader.
+            entry_point: "fragment".into(),
+            targets: vec![Some(ColorTargetState {
+                format: key.texture_format,
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            })],
+        }),
+        // All of the following properties are not important for this effect so just use the default values.
+        // This struct doesn't have the Default trait implemented because not all field can have a default value.
+        primitive: PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: MultisampleState::default(),
+        push_constant_ranges: vec![],
+        zero_initialize_workgroup_memory: false,
     }
+}
 
-    ///TODO
-    pub fn changed(&self) -> bool {
-        self.changed
+impl<T: SimplePostProcess> SpecializedRenderPipeline for PostProcessPipeline<T> {
+    type Key = PostProcessPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        post_process_pipeline_descriptor(
+            effect_name(T::Label::default()),
+            self.layouts.clone(),
+            key,
+            self.view_layouts.as_ref(),
+        )
     }
+}
 
+///TODO
+#[derive(Resource, ExtractResource, Clone)]
+pub struct PostProcessImage<T: SimplePostProcess> {
     ///TODO
-    pub fn clear_changed(&mut self) {
-        self.changed = false;
+    pub texture_inputs: TextureInputs,
+    _marker: PhantomData<T>,
+}
+
+/// The shader definitions an effect uses on a specific view.
+#[derive(Component)]
+pub struct ViewPostProcessShaderDefs<T> {
+    /// See [`SimplePostProcess::shader_defs`].
+    pub shader_defs: Vec<ShaderDefVal>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: SimplePostProcess> ExtractComponent for ViewPostProcessShaderDefs<T> {
+    type QueryData = &'static T;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(effect: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        Some(Self {
+            shader_defs: effect.shader_defs(),
+            _marker: PhantomData,
+        })
     }
 }
 
-fn post_process_shader_def_system<T: SimplePostProcess>(
-    mut shader_def: ResMut<PostProcessShaderDef<T>>,
-    mut pipeline: ResMut<PostProcessPipeline<T>>,
+fn prepare_post_process_pipelines<T: SimplePostProcess>(
+    mut commands: Commands,
     pipeline_cache: Res<PipelineCache>,
-    asset_server: Res<AssetServer>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<PostProcessPipeline<T>>>,
+    post_process_pipeline: Res<PostProcessPipeline<T>>,
+    shader: Res<EffectShader<T>>,
+    views: Query<(
+        Entity,
+        &ViewTarget,
+        &ViewPostProcessShaderDefs<T>,
+        Option<&ViewBindingsKey>,
+    )>,
 ) {
-    if shader_def.changed() {
-        let shader = asset_server.load(T::shader_path());
-        let pipeline_id = pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
-            label: Some("post_process_pipeline".into()),
-            layout: pipeline.layouts.clone(),
-            vertex: fullscreen_shader_vertex_state(),
-            fragment: Some(FragmentState {
-                shader,
-                shader_defs: shader_def.shader_defs().to_vec(),
-                entry_point: "fragment".into(),
-                targets: vec![Some(ColorTargetState {
-                    format: TextureFormat::bevy_default(),
-                    blend: None,
-                    write_mask: ColorWrites::ALL,
-                })],
-            }),
-            primitive: PrimitiveState::default(),
-            depth_stencil: None,
-            multisample: MultisampleState::default(),
-            push_constant_ranges: vec![],
-            zero_initialize_workgroup_memory: false,
-        });
-        pipeline.pipeline_id = pipeline_id;
-        shader_def.clear_changed();
+    for (entity, view_target, shader_defs, view_bindings) in &views {
+        let id = pipelines.specialize(
+            &pipeline_cache,
+            &post_process_pipeline,
+            PostProcessPipelineKey {
+                shader: shader.handle.clone(),
+                texture_format: view_target.main_texture_format(),
+                shader_defs: shader_defs.shader_defs.clone(),
+                view_bindings: T::view_bindings()
+                    .then(|| view_bindings.copied().unwrap_or_default()),
+            },
+        );
+
+        commands
+            .entity(entity)
+            .insert(ViewPostProcessPipeline::<T>::new(id));
     }
 }
 
+/// The bind group of an effect `T`, e.g. a [`SimplePostProcess`].
+///
+/// It is shared by all views, which pick their uniform via a dynamic offset.
+#[derive(Resource)]
+pub(crate) struct EffectBindGroup<T> {
+    pub(crate) bind_group: Option<BindGroup>,
+    /// The resources the bind group was created from.
+    inputs: Option<(BufferId, Vec<Option<TextureViewId>>)>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for EffectBindGroup<T> {
+    fn default() -> Self {
+        Self {
+            bind_group: None,
+            inputs: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> EffectBindGroup<T> {
+    /// Creates the bind group for the uniforms `U`, if their buffer or the textures changed
+    /// since last time.
+    pub(crate) fn prepare<U: Component + ShaderType + WriteInto>(
+        &mut self,
+        world: &World,
+        layout: &BindGroupLayout,
+        texture_inputs: &TextureInputs,
+        create: impl FnOnce(
+            &World,
+            &RenderDevice,
+            &BindGroupLayout,
+            BindingResource,
+            &TextureInputs,
+        ) -> Option<BindGroup>,
+    ) {
+        let uniforms = world.resource::<ComponentUniforms<U>>().uniforms();
+        let (Some(buffer), Some(binding)) = (uniforms.buffer(), uniforms.binding()) else {
+            // No view uses the effect.
+            *self = Self::default();
+            return;
+        };
+
+        let images = world.resource::<RenderAssets<GpuImage>>();
+        let inputs = Some((
+            buffer.id(),
+            texture_inputs
+                .handles()
+                .iter()
+                .map(|handle| images.get(handle).map(|image| image.texture_view.id()))
+                .collect::<Vec<_>>(),
+        ));
+
+        if self.inputs == inputs {
+            return;
+        }
+
+        self.bind_group = create(
+            world,
+            world.resource::<RenderDevice>(),
+            layout,
+            binding,
+            texture_inputs,
+        );
+        self.inputs = inputs;
+    }
+}
+
+fn prepare_post_process_bind_group<T: SimplePostProcess>(world: &mut World) {
+    world.resource_scope(|world, mut cached: Mut<EffectBindGroup<T>>| {
+        cached.prepare::<T::Out>(
+            world,
+            &world.resource::<PostProcessPipeline<T>>().layouts[1],
+            &world.resource::<PostProcessImage<T>>().texture_inputs,
+            T::bind_group,
+        );
+    });
+}
+
 ///TODO
 #[derive(Debug, Clone, Default)]
 pub enum TextureInputs {
@@ -170,6 +367,17 @@ pub enum TextureInputs {
     Multiple(Vec<Handle<Image>>),
 }
 
+impl TextureInputs {
+    /// The images, in order.
+    pub fn handles(&self) -> &[Handle<Image>] {
+        match self {
+            TextureInputs::None => &[],
+            TextureInputs::Single(handle) => std::slice::from_ref(handle),
+            TextureInputs::Multiple(handles) => handles,
+        }
+    }
+}
+
 impl<T: SimplePostProcess> FromWorld for PostProcessImage<T> {
     fn from_world(world: &mut World) -> Self {
         let texture_inputs = T::textures(world);
@@ -182,69 +390,19 @@ impl<T: SimplePostProcess> FromWorld for PostProcessImage<T> {
 
 impl<T: SimplePostProcess> FromWorld for PostProcessPipeline<T> {
     fn from_world(world: &mut World) -> Self {
+        let view_layouts =
+            T::view_bindings().then(|| world.get_resource_or_init::<ViewBindingsLayouts>().clone());
         let render_device = world.resource::<RenderDevice>();
 
-        // We need to define the bind group layout used for our pipeline
-        let layout = render_device.create_bind_group_layout(
-            "post_process_bind_group_layout",
-            &BindGroupLayoutEntries::sequential(
-                // The layout entries will only be visible in the fragment stage
-                ShaderStages::FRAGMENT,
-                (
-                    // The screen texture
-                    texture_2d(TextureSampleType::Float { filterable: true }),
-                    // The sampler that will be used to sample the screen texture
-                    sampler(SamplerBindingType::Filtering),
-                    // The settings uniform that will control the effect
-                    uniform_buffer::<GlobalsUniform>(false),
-                ),
-            ),
-        );
-
-        let layouts = vec![layout, T::layout(render_device)];
+        let layouts = vec![shared_layout(render_device), T::layout(render_device)];
 
         // We can create the sampler here since it won't change at runtime and doesn't depend on the view
         let sampler = render_device.create_sampler(&SamplerDescriptor::default());
 
-        // Get the shader handle
-        let shader = world.load_asset(T::shader_path());
-
-        let pipeline_id = world
-            .resource_mut::<PipelineCache>()
-            // This will add the pipeline to the cache and queue it's creation
-            .queue_render_pipeline(RenderPipelineDescriptor {
-                label: Some("post_process_pipeline".into()),
-                layout: layouts.clone(),
-                // This will setup a fullscreen triangle for the vertex state
-                vertex: fullscreen_shader_vertex_state(),
-                fragment: Some(FragmentState {
-                    shader,
-                    shader_defs: T::shader_defs(),
-                    // Make sure this matches the entry point of your shader.
-                    // It can be anything as long as it matches here and in the shader.
-                    entry_point: "fragment".into(),
-                    targets: vec![Some(ColorTargetState {
-                        format: TextureFormat::bevy_default(),
-                        blend: None,
-                        write_mask: ColorWrites::ALL,
-                    })],
-                }),
-                // All of the following properties are not important for this effect so just use the default values.
-                // This struct doesn't have the Default trait implemented because not all field can have a default value.
-                primitive: PrimitiveState::default(),
-                depth_stencil: None,
-                multisample: MultisampleState::default(),
-                push_constant_ranges: vec![],
-                zero_initialize_workgroup_memory: false,
-            });
-
-        //let textures = T::textures(world);
-
         Self {
             layouts,
+            view_layouts,
             sampler,
-            pipeline_id,
-            //textures,
             _marker: std::marker::PhantomData,
         }
     }
@@ -256,40 +414,70 @@ impl<T: SimplePostProcess> FromWorld for PostProcessPipeline<T> {
 pub struct PostProcessNode<T: SimplePostProcess>(PhantomData<fn() -> T>);
 
 // The ViewNode trait is required by the ViewNodeRunner
-impl<T: SimplePostProcess + WriteInto + ShaderType> ViewNode for PostProcessNode<T> {
-    type ViewQuery = (&'static ViewTarget, &'static DynamicUniformIndex<T>);
+impl<T: SimplePostProcess> ViewNode for PostProcessNode<T> {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static DynamicUniformIndex<T::Out>,
+        &'static ViewPostProcessPipeline<T>,
+        Option<&'static EffectWeight<T>>,
+        Option<&'static ViewEffectBlend<T>>,
+        Option<&'static ViewEffectResolution<T>>,
+        Option<&'static ViewBindings>,
+    );
 
     fn run(
         &self,
-        _graph: &mut RenderGraphContext,
+        graph: &mut RenderGraphContext,
         render_context: &mut RenderContext,
-        (view_target, settings_index): QueryItem<Self::ViewQuery>,
+        (view_target, settings_index, view_pipeline, weight, blend, resolution, view_bindings): QueryItem<
+            Self::ViewQuery,
+        >,
         world: &World,
     ) -> Result<(), NodeRunError> {
+        if is_weightless(weight) {
+            return Ok(());
+        }
+
         // Get the pipeline resource that contains the global data we need
         // to create the render pipeline
         let post_process_pipeline = world.resource::<PostProcessPipeline<T>>();
 
-        let post_process_image = world.resource::<PostProcessImage<T>>();
-
         // The pipeline cache is a cache of all previously created pipelines.
         // It is required to avoid creating a new pipeline each frame,
         // which is expensive due to shader compilation.
         let pipeline_cache = world.resource::<PipelineCache>();
 
         // Get the pipeline from the cache
-        let Some(pipeline) = pipeline_cache.get_render_pipeline(post_process_pipeline.pipeline_id)
-        else {
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(view_pipeline.id) else {
             return Ok(());
         };
 
-        // Get the settings uniform binding
-        let settings_uniforms = world.resource::<ComponentUniforms<T>>();
-        let Some(settings_binding) = settings_uniforms.uniforms().binding() else {
+        // A partially applied effect is only shown once it can be blended with its input.
+        if blend.is_some_and(|blend| !blend.is_ready(world)) {
+            return Ok(());
+        }
+        // Likewise an effect at reduced resolution once it can be upsampled.
+        if resolution.is_some_and(|resolution| !resolution.is_ready(world)) {
+            return Ok(());
+        }
+
+        // Effects reading the view wait for its prepass textures.
+        if T::view_bindings() && view_bindings.is_none() {
+            return Ok(());
+        }
+
+        // Get the effect's bind group, see `prepare_post_process_bind_group`
+        let Some(bind_group) = &world.resource::<EffectBindGroup<T>>().bind_group else {
+            report_skipped::<T>(
+                world,
+                graph.view_entity(),
+                T::Label::default(),
+                SkipReason::BindGroupUnavailable,
+            );
             return Ok(());
         };
 
-        let Some(globals) = world.resource::<GlobalsBuffer>().buffer.binding() else {
+        let Some(globals) = globals_binding(world, graph.view_entity()) else {
             return Ok(());
         };
 
@@ -302,9 +490,9 @@ impl<T: SimplePostProcess + WriteInto + ShaderType> ViewNode for PostProcessNode
         // the current main texture information to be lost.
         let post_process = view_target.post_process_write();
 
-        // The bind_group gets created each frame.
+        // The shared bind_group gets created each frame.
         //
-        // Normally, you would create a bind_group in the Queue set,
+        // Normally, you would create a bind_group in the PrepareBindGroups set,
         // but this doesn't work with the post_process_write().
         // The reason it doesn't work is because each post_process_write will alternate the source/destination.
         // The only way to have the correct source/destination for the bind_group
@@ -323,19 +511,16 @@ impl<T: SimplePostProcess + WriteInto + ShaderType> ViewNode for PostProcessNode
             )),
         );
 
-        let bind_group = T::bind_group(
-            world,
-            render_context.render_device(),
-            &post_process_pipeline.layouts[1],
-            settings_binding.clone(),
-            &post_process_image.texture_inputs,
-        );
+        // At reduced resolution the effect renders into an intermediate texture
+        let target: &TextureView =
+            resolution.map_or(post_process.destination, ViewEffectResolution::target);
 
-        // Begin the render pass
+        // Begin the render pass, named after the effect so it can be told apart in GPU tools
+        let label = effect_name(T::Label::default());
         let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
-            label: Some("post_process_pass"),
+            label: Some(&label),
             color_attachments: &[Some(RenderPassColorAttachment {
-                view: post_process.destination,
+                view: target,
                 resolve_target: None,
                 ops: Operations::default(),
             })],
@@ -347,8 +532,30 @@ impl<T: SimplePostProcess + WriteInto + ShaderType> ViewNode for PostProcessNode
         render_pass.set_render_pipeline(pipeline);
 
         render_pass.set_bind_group(0, &shared_bind_group, &[]);
-        render_pass.set_bind_group(1, &bind_group, &[settings_index.index()]);
+        render_pass.set_bind_group(1, bind_group, &[settings_index.index()]);
+        if let Some(view_bindings) = view_bindings.filter(|_| T::view_bindings()) {
+            render_pass.set_bind_group(2, view_bindings.bind_group(), &[view_bindings.offset()]);
+        }
         render_pass.draw(0..3, 0..1);
+        drop(render_pass);
+
+        if let Some(resolution) = resolution {
+            resolution.upsample(
+                render_context,
+                world,
+                post_process.source,
+                post_process.destination,
+            );
+        }
+
+        if let Some(blend) = blend {
+            blend.render(
+                render_context,
+                world,
+                post_process.source,
+                post_process.destination,
+            );
+        }
 
         Ok(())
     }
@@ -363,33 +570,61 @@ impl<T: SimplePostProcess> Default for SimplePostProcessPlugin<T> {
     }
 }
 
-impl<T: SimplePostProcess + WriteInto + ShaderType> Plugin for SimplePostProcessPlugin<T> {
+impl<T: SimplePostProcess> Plugin for SimplePostProcessPlugin<T> {
     fn build(&self, app: &mut App) {
         app.add_plugins((
             ExtractComponentPlugin::<T>::default(),
-            UniformComponentPlugin::<T>::default(),
+            ExtractComponentPlugin::<ViewPostProcessShaderDefs<T>>::default(),
+            UniformComponentPlugin::<T::Out>::default(),
             ExtractResourcePlugin::<PostProcessImage<T>>::default(),
-            ExtractResourcePlugin::<PostProcessShaderDef<T>>::default(),
+            ExtractResourcePlugin::<EffectShader<T>>::default(),
         ));
 
+        if !app.is_plugin_added::<EffectBlendPlugin<T>>() {
+            app.add_plugins(EffectBlendPlugin::<T>::default());
+        }
+        if !app.is_plugin_added::<EffectResolutionPlugin<T>>() {
+            app.add_plugins(EffectResolutionPlugin::<T>::default());
+        }
+
+        if T::view_bindings() {
+            add_view_bindings(app);
+        }
+
+        init_effect_shader::<T>(app, T::shader_path());
+
+        register_effect::<T>(app, T::Label::default(), |world| {
+            Box::new(ViewNodeRunner::new(PostProcessNode::<T>::default(), world))
+        });
+
+        if let Some(stage) = T::fusion_stage() {
+            load_shader_module(app, stage.module.clone());
+            if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+                render_app
+                    .world_mut()
+                    .resource_mut::<PostProcessEffects>()
+                    .set_fusion(T::Label::default(), FusableEffect::new::<T>(stage));
+            }
+        }
+
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
 
         render_app
+            .init_resource::<SpecializedRenderPipelines<PostProcessPipeline<T>>>()
+            .init_resource::<EffectBindGroup<T>>()
             .add_systems(
                 Render,
-                post_process_shader_def_system::<T>.in_set(RenderSet::Queue),
-            )
-            .add_render_graph_node::<ViewNodeRunner<PostProcessNode<T>>>(
-                Core3d,
-                T::Label::default(),
+                (
+                    prepare_post_process_pipelines::<T>.in_set(RenderSet::Prepare),
+                    prepare_post_process_bind_group::<T>.in_set(RenderSet::PrepareBindGroups),
+                ),
             );
     }
 
     fn finish(&self, app: &mut App) {
-        app.init_resource::<PostProcessImage<T>>()
-            .insert_resource(PostProcessShaderDef::<T>::default());
+        app.init_resource::<PostProcessImage<T>>();
 
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
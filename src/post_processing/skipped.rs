@@ -0,0 +1,123 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_graph::{InternedRenderLabel, RenderLabel},
+        sync_world::MainEntity,
+        RenderApp,
+    },
+    utils::HashSet,
+};
+
+/// Sent when an effect could not be rendered on a camera, so the camera's image was passed through
+/// unchanged for that frame.
+///
+/// Each effect is reported once per camera and reason, along with a warning.
+#[derive(Event, Debug, Clone)]
+pub struct EffectSkipped {
+    /// The type name of the effect.
+    pub effect: &'static str,
+    /// The render label of the effect, see [`super::stack::PostProcessingOrder`].
+    pub label: InternedRenderLabel,
+    /// The camera the effect was skipped on.
+    pub camera: Entity,
+    /// Why the effect was skipped.
+    pub reason: SkipReason,
+}
+
+/// Why an effect was skipped, see [`EffectSkipped`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SkipReason {
+    /// The effect's bind group is not available, e.g. while its textures are still loading.
+    BindGroupUnavailable,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::BindGroupUnavailable => write!(f, "its bind group is not available"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct SkippedEffectsState {
+    reported: HashSet<(InternedRenderLabel, Entity, SkipReason)>,
+    /// Reported effects not yet sent as events.
+    pending: Vec<EffectSkipped>,
+}
+
+/// The effects skipped in the render world, shared with the main world to send [`EffectSkipped`].
+#[derive(Resource, Clone, Default)]
+pub(crate) struct SkippedEffects(Arc<Mutex<SkippedEffectsState>>);
+
+impl SkippedEffects {
+    /// Reports that the effect `T` was skipped on a main world camera, unless it already was
+    /// for the same reason.
+    pub(crate) fn report<T: 'static>(
+        &self,
+        camera: Entity,
+        label: impl RenderLabel,
+        reason: SkipReason,
+    ) {
+        let label = label.intern();
+
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if !state.reported.insert((label, camera, reason)) {
+            return;
+        }
+
+        let effect = std::any::type_name::<T>();
+        warn!("Skipping {effect} on camera {camera}: {reason}");
+        state.pending.push(EffectSkipped {
+            effect,
+            label,
+            camera,
+            reason,
+        });
+    }
+}
+
+/// Reports that the effect `T` was skipped on a render world view, see [`SkippedEffects::report`].
+pub(crate) fn report_skipped<T: 'static>(
+    world: &World,
+    view: Entity,
+    label: impl RenderLabel,
+    reason: SkipReason,
+) {
+    if let Some(skipped) = world.get_resource::<SkippedEffects>() {
+        let camera = world.get::<MainEntity>(view).map_or(view, MainEntity::id);
+        skipped.report::<T>(camera, label, reason);
+    }
+}
+
+fn send_skipped_effects(skipped: Res<SkippedEffects>, mut events: EventWriter<EffectSkipped>) {
+    let mut state = skipped.0.lock().unwrap_or_else(PoisonError::into_inner);
+    events.send_batch(state.pending.drain(..));
+}
+
+/// Sends [`EffectSkipped`] for effects the render world could not render.
+///
+/// Added by the effect plugins, so it does not need to be added manually.
+#[derive(Debug, Default)]
+pub(crate) struct EffectSkippedPlugin;
+
+impl Plugin for EffectSkippedPlugin {
+    fn build(&self, app: &mut App) {
+        let skipped = SkippedEffects::default();
+        app.add_event::<EffectSkipped>()
+            .insert_resource(skipped.clone())
+            .add_systems(First, send_skipped_effects);
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.insert_resource(skipped);
+    }
+}
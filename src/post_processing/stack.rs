@@ -0,0 +1,392 @@
+use bevy::{
+    core_pipeline::{
+        core_2d::graph::{Core2d, Node2d},
+        core_3d::graph::{Core3d, Node3d},
+    },
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_graph::{
+            InternedRenderLabel, Node, NodeRunError, RenderGraphApp, RenderGraphContext,
+            RenderLabel,
+        },
+        renderer::RenderContext,
+        sync_world::MainEntity,
+        view::ViewTarget,
+        RenderApp,
+    },
+    utils::HashMap,
+};
+
+use super::{
+    blend::{is_weightless, EffectWeight},
+    clock::EffectClockPlugin,
+    comparison::{EffectComparisonPlugin, ViewEffectComparison},
+    diagnostics::{effect_name, fused_effect_name, in_effect_span},
+    fusion::{FusableEffect, PostProcessFusionPlugin, ViewFusedChains},
+    simple_post_process::ViewPostProcessPipeline,
+    skipped::EffectSkippedPlugin,
+};
+
+/// The render graph label of the node running every post processing effect of a view.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
+pub struct PostProcessStackLabel;
+
+/// The order effects are applied in on a camera.
+///
+/// Effects are identified by their render labels, e.g. [`super::wave::WavePostProcessLabel`].
+/// Effects present on the camera but not listed here run afterwards, in the default order.
+///
+/// The order is read each frame, so it may be changed at runtime.
+///
+/// ```rust,ignore
+/// commands.spawn((
+///     Camera3d::default(),
+///     Wave::default(),
+///     Pixelate::default(),
+///     PostProcessingOrder::new()
+///         .then(PixelatePostProcessLabel)
+///         .then(WavePostProcessLabel),
+/// ));
+/// ```
+#[derive(Debug, Default, Clone, Component, ExtractComponent)]
+pub struct PostProcessingOrder {
+    labels: Vec<InternedRenderLabel>,
+}
+
+impl PostProcessingOrder {
+    /// An empty ordering, i.e. the default order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the effect with the given label after the effects already listed.
+    pub fn then(mut self, label: impl RenderLabel) -> Self {
+        self.push(label);
+        self
+    }
+
+    /// Adds the effect with the given label after the effects already listed.
+    /// If the effect was already listed it is moved to the back.
+    pub fn push(&mut self, label: impl RenderLabel) {
+        let label = label.intern();
+        self.labels.retain(|listed| *listed != label);
+        self.labels.push(label);
+    }
+
+    /// Removes all listed effects, returning to the default order.
+    pub fn clear(&mut self) {
+        self.labels.clear();
+    }
+
+    /// The listed effects, in order.
+    pub fn labels(&self) -> &[InternedRenderLabel] {
+        &self.labels
+    }
+
+    /// The listed effects, in order.
+    /// Mutable access allows e.g. swapping or rotating effects.
+    pub fn labels_mut(&mut self) -> &mut Vec<InternedRenderLabel> {
+        &mut self.labels
+    }
+}
+
+/// Creates the node which runs an effect on a view.
+pub(crate) type NewEffectNode = fn(&mut World) -> Box<dyn Node>;
+
+/// An effect known to the [`PostProcessStackNode`].
+pub(crate) struct RegisteredEffect {
+    label: InternedRenderLabel,
+    new_node: NewEffectNode,
+    /// Whether the effect runs on a view.
+    pub(crate) is_applied: fn(&World, Entity) -> bool,
+    /// How to render the effect as part of a fused pass, if it can be.
+    pub(crate) fusion: Option<FusableEffect>,
+}
+
+/// All effects known to the [`PostProcessStackNode`], in default order.
+#[derive(Resource, Default)]
+pub(crate) struct PostProcessEffects {
+    effects: Vec<RegisteredEffect>,
+}
+
+impl PostProcessEffects {
+    /// Registers an effect. Effects run in registration order unless
+    /// [`Self::set_default_order`] or a [`PostProcessingOrder`] says otherwise.
+    pub(crate) fn register(
+        &mut self,
+        label: impl RenderLabel,
+        new_node: NewEffectNode,
+        is_applied: fn(&World, Entity) -> bool,
+    ) {
+        let label = label.intern();
+        if self.get(label).is_none() {
+            self.effects.push(RegisteredEffect {
+                label,
+                new_node,
+                is_applied,
+                fusion: None,
+            });
+        }
+    }
+
+    /// Allows a registered effect to be fused with adjacent ones, see [`super::fusion::PostProcessFusion`].
+    pub(crate) fn set_fusion(&mut self, label: impl RenderLabel, fusion: FusableEffect) {
+        let label = label.intern();
+        if let Some(effect) = self.effects.iter_mut().find(|effect| effect.label == label) {
+            effect.fusion = Some(fusion);
+        }
+    }
+
+    /// The registered effect with the given label.
+    pub(crate) fn get(&self, label: InternedRenderLabel) -> Option<&RegisteredEffect> {
+        self.effects.iter().find(|effect| effect.label == label)
+    }
+
+    /// Sorts the registered effects by their position in the given labels.
+    /// Effects not in the given labels keep their relative order, after the given ones.
+    pub(crate) fn set_default_order(&mut self, labels: &[InternedRenderLabel]) {
+        self.effects.sort_by_key(|effect| {
+            labels
+                .iter()
+                .position(|ordered| *ordered == effect.label)
+                .unwrap_or(labels.len())
+        });
+    }
+
+    /// The order effects run in on a view with the given (optional) ordering.
+    pub(crate) fn order(&self, order: Option<&PostProcessingOrder>) -> Vec<InternedRenderLabel> {
+        let listed = order.map(PostProcessingOrder::labels).unwrap_or_default();
+
+        listed
+            .iter()
+            .filter(|label| self.get(**label).is_some())
+            .chain(
+                self.effects
+                    .iter()
+                    .map(|effect| &effect.label)
+                    .filter(|label| !listed.contains(label)),
+            )
+            .copied()
+            .collect()
+    }
+}
+
+/// Runs all registered effects on a view, in the order given by the view's [`PostProcessingOrder`].
+///
+/// Having a single node run the stack means the render graph does not change when
+/// the order does.
+pub(crate) struct PostProcessStackNode {
+    nodes: HashMap<InternedRenderLabel, Box<dyn Node>>,
+    view_query: QueryState<ViewStackQuery>,
+}
+
+/// What the [`PostProcessStackNode`] reads from a view.
+type ViewStackQuery = (
+    &'static ViewTarget,
+    Option<&'static PostProcessingOrder>,
+    Option<&'static ViewFusedChains>,
+    Option<&'static ViewEffectComparison>,
+);
+
+impl FromWorld for PostProcessStackNode {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            nodes: HashMap::default(),
+            view_query: world.query(),
+        }
+    }
+}
+
+impl Node for PostProcessStackNode {
+    fn update(&mut self, world: &mut World) {
+        // Effects may have been registered after this node was created.
+        let missing = world
+            .resource::<PostProcessEffects>()
+            .effects
+            .iter()
+            .filter(|effect| !self.nodes.contains_key(&effect.label))
+            .map(|effect| (effect.label, effect.new_node))
+            .collect::<Vec<_>>();
+
+        for (label, new_node) in missing {
+            self.nodes.insert(label, new_node(world));
+        }
+
+        self.view_query.update_archetypes(world);
+        for node in self.nodes.values_mut() {
+            node.update(world);
+        }
+    }
+
+    fn run<'w>(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext<'w>,
+        world: &'w World,
+    ) -> Result<(), NodeRunError> {
+        let Ok((view_target, order, fused_chains, comparison)) =
+            self.view_query.get_manual(world, graph.view_entity())
+        else {
+            return Ok(());
+        };
+
+        let view = graph.view_entity();
+        // Diagnostics are reported for the main world camera.
+        let camera = world.get::<MainEntity>(view).map_or(view, MainEntity::id);
+        let effects = world.resource::<PostProcessEffects>();
+
+        // The image before the compared effects is stored and drawn over the image after them.
+        let comparison = comparison.filter(|comparison| comparison.is_ready(world));
+        if let Some(comparison) = comparison.filter(|comparison| comparison.compares_stack()) {
+            comparison.store(render_context, world, view_target);
+        }
+
+        // Effects already rendered as part of a fused pass.
+        let mut fused = Vec::new();
+
+        for label in effects.order(order) {
+            if fused.contains(&label) {
+                continue;
+            }
+
+            // A compared effect is rendered on its own, so its input can be stored.
+            if let Some(chain) = fused_chains
+                .and_then(|chains| chains.starting_with(label))
+                .filter(|chain| {
+                    comparison.is_none_or(|comparison| {
+                        !chain
+                            .labels()
+                            .iter()
+                            .any(|label| comparison.compares(*label))
+                    })
+                })
+            {
+                let name = fused_effect_name(chain.labels());
+                if in_effect_span(render_context, camera, &name, |render_context| {
+                    chain.render(render_context, world, view, view_target)
+                }) {
+                    fused.extend_from_slice(chain.labels());
+                    continue;
+                }
+            }
+
+            let Some(node) = self.nodes.get(&label) else {
+                continue;
+            };
+
+            let compared = comparison.filter(|comparison| comparison.compares(label));
+            if let Some(comparison) = compared {
+                comparison.store(render_context, world, view_target);
+            }
+
+            // Only effects running on the view are measured, so others add no diagnostics.
+            if effects
+                .get(label)
+                .is_some_and(|effect| (effect.is_applied)(world, view))
+            {
+                in_effect_span(
+                    render_context,
+                    camera,
+                    &effect_name(label),
+                    |render_context| node.run(graph, render_context, world),
+                )?;
+            } else {
+                node.run(graph, render_context, world)?;
+            }
+
+            if let Some(comparison) = compared {
+                comparison.render(render_context, world, view_target);
+            }
+        }
+
+        if let Some(comparison) = comparison.filter(|comparison| comparison.compares_stack()) {
+            comparison.render(render_context, world, view_target);
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds the node running the effect stack to the 2D and 3D render graphs.
+///
+/// Added by the effect plugins, so it does not need to be added manually.
+#[derive(Debug, Default)]
+pub(crate) struct PostProcessStackPlugin;
+
+impl Plugin for PostProcessStackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<PostProcessingOrder>::default(),
+            PostProcessFusionPlugin,
+            EffectClockPlugin,
+            EffectSkippedPlugin,
+            EffectComparisonPlugin,
+        ));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<PostProcessEffects>()
+            .add_render_graph_node::<PostProcessStackNode>(Core3d, PostProcessStackLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::Tonemapping,
+                    PostProcessStackLabel,
+                    Node3d::EndMainPassPostProcessing,
+                ),
+            )
+            .add_render_graph_node::<PostProcessStackNode>(Core2d, PostProcessStackLabel)
+            .add_render_graph_edges(
+                Core2d,
+                (
+                    Node2d::Tonemapping,
+                    PostProcessStackLabel,
+                    Node2d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+}
+
+/// Whether the effect `T` runs on a view, i.e. has a pipeline there and is not weightless.
+fn is_effect_applied<T: 'static>(world: &World, view: Entity) -> bool {
+    world.get_entity(view).is_ok_and(|view| {
+        view.contains::<ViewPostProcessPipeline<T>>()
+            && !is_weightless(view.get::<EffectWeight<T>>())
+    })
+}
+
+/// Registers the effect `T` with the effect stack, adding the stack if needed.
+pub(crate) fn register_effect<T: 'static>(
+    app: &mut App,
+    label: impl RenderLabel,
+    new_node: NewEffectNode,
+) {
+    register_effect_node(app, label, new_node, is_effect_applied::<T>);
+}
+
+/// Registers an effect with the effect stack, adding the stack if needed.
+///
+/// `is_applied` tells whether the effect runs on a view.
+pub(crate) fn register_effect_node(
+    app: &mut App,
+    label: impl RenderLabel,
+    new_node: NewEffectNode,
+    is_applied: fn(&World, Entity) -> bool,
+) {
+    if !app.is_plugin_added::<PostProcessStackPlugin>() {
+        app.add_plugins(PostProcessStackPlugin);
+    }
+
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+
+    render_app
+        .world_mut()
+        .resource_mut::<PostProcessEffects>()
+        .register(label, new_node, is_applied);
+}
@@ -7,9 +7,13 @@ use bevy::{
 };
 use binding_types::uniform_buffer;
 
-use super::simple_post_process::{SimplePostProcess, TextureInputs};
+use super::{
+    shader::embedded_shader_path,
+    simple_post_process::{SimplePostProcess, TextureInputs},
+};
 ///TODO
 #[derive(Component, Default, Clone, Copy, ExtractComponent, ShaderType)]
+#[cfg_attr(test, derive(Reflect))]
 pub struct TestPostProcessSettings {
     ///TODO
     pub intensity: f32,
@@ -20,12 +24,7 @@ pub struct TestPostProcessSettings {
 
 impl SimplePostProcess for TestPostProcessSettings {
     fn shader_path() -> String {
-        concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/assets/shaders/",
-            "post_processing.wgsl"
-        )
-        .into()
+        embedded_shader_path("post_processing.wgsl")
     }
     type Label = TestPostProcessLabel;
     fn layout(device: &RenderDevice) -> BindGroupLayout {
@@ -43,12 +42,12 @@ impl SimplePostProcess for TestPostProcessSettings {
         layout: &BindGroupLayout,
         buffer: BindingResource,
         _textures: &TextureInputs,
-    ) -> BindGroup {
-        device.create_bind_group(
+    ) -> Option<BindGroup> {
+        Some(device.create_bind_group(
             "flip_bind_group",
             layout,
             &BindGroupEntries::sequential((buffer,)),
-        )
+        ))
     }
 }
 
@@ -0,0 +1,409 @@
+use std::{
+    any::{Any, TypeId},
+    marker::PhantomData,
+};
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{blend::EffectWeight, stack::PostProcessingOrder, tween::EffectLerp};
+
+/// Changes the effects on a camera to another set over time, without popping.
+///
+/// ```rust,ignore
+/// // Dive underwater over two seconds.
+/// commands.entity(camera).insert(
+///     StackTransition::new(2.0)
+///         .with(Wave { amplitude_x: 0.07, ..default() })
+///         .with(Lut::arctic(&mut luts))
+///         .with(Mask::vignette()),
+/// );
+/// ```
+///
+/// When done the camera has exactly the effects given. Meanwhile:
+///
+/// - Effects only on the camera fade out via their [`EffectWeight`], and are then removed.
+/// - Effects only in the transition are added, and fade in.
+/// - Effects in both have their parameters interpolated if they implement [`EffectLerp`] and
+///   [`EffectLerp::can_lerp`] allows it, otherwise they fade out and back in with the new parameters.
+///
+/// Each effect is still rendered once, so this costs no more than the larger of both sets.
+/// Inserting another transition while one plays continues from the effects as they are shown.
+/// Only effects with an [`EffectTransitionPlugin`] take part, which are all effects of this crate.
+#[derive(Component)]
+pub struct StackTransition {
+    targets: Targets,
+    order: Option<PostProcessingOrder>,
+    duration: f32,
+    ease: EaseFunction,
+}
+
+impl StackTransition {
+    /// A transition to no effects taking the given seconds.
+    ///
+    /// Add the effects to transition to with [`Self::with`].
+    pub fn new(duration: f32) -> Self {
+        Self {
+            targets: HashMap::default(),
+            order: None,
+            duration: duration.max(0.0),
+            ease: EaseFunction::Linear,
+        }
+    }
+
+    /// Transitions to the given effect.
+    pub fn with<T: Component + Clone>(mut self, effect: T) -> Self {
+        self.targets.insert(TypeId::of::<T>(), Box::new(effect));
+        self
+    }
+
+    /// Applies the effects in the given order, from the start of the transition.
+    pub fn with_order(mut self, order: PostProcessingOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Eases the transition with the given function.
+    pub fn with_ease(mut self, ease: EaseFunction) -> Self {
+        self.ease = ease;
+        self
+    }
+}
+
+/// Sent when a [`StackTransition`] is done.
+#[derive(Debug, Event)]
+pub struct StackTransitionCompleted {
+    /// The camera the transition was on.
+    pub camera: Entity,
+}
+
+/// A [`StackTransition`] playing on a camera.
+#[derive(Component)]
+struct ActiveTransition {
+    tracks: Vec<Box<dyn TransitionTrack>>,
+    duration: f32,
+    ease: EaseFunction,
+    elapsed: f32,
+}
+
+impl ActiveTransition {
+    /// How far the transition is, from `0.0` to `1.0`, before easing.
+    fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        (self.elapsed / self.duration).min(1.0)
+    }
+}
+
+/// How one effect of a [`StackTransition`] changes.
+trait TransitionTrack: Send + Sync + Any {
+    /// Shows the effect the given eased fraction through the transition.
+    fn update(&mut self, camera: &mut EntityWorldMut, t: f32);
+
+    /// Leaves the effect as it should be after the transition.
+    fn finish(&mut self, camera: &mut EntityWorldMut);
+
+    /// The track as [`Any`], to find the track of an effect in an interrupted transition.
+    fn as_any(&self) -> &dyn Any;
+}
+
+struct EffectTrack<T: Component + Clone> {
+    from: Option<T>,
+    to: Option<T>,
+    /// The weight shown when the transition started.
+    from_weight: f32,
+    /// The weight the camera had for the effect before any transition, restored when done.
+    weight: Option<EffectWeight<T>>,
+    lerp: Option<fn(&T, &T, f32) -> T>,
+    /// Whether the target effect is on the camera yet.
+    showing_target: bool,
+}
+
+impl<T: Component + Clone> EffectTrack<T> {
+    fn to_weight(&self) -> f32 {
+        self.weight.map_or(1.0, |weight| weight.weight)
+    }
+
+    fn show_target(&mut self, camera: &mut EntityWorldMut) {
+        if !self.showing_target {
+            if let Some(to) = &self.to {
+                camera.insert(to.clone());
+            }
+            self.showing_target = true;
+        }
+    }
+}
+
+impl<T: Component + Clone> TransitionTrack for EffectTrack<T> {
+    fn update(&mut self, camera: &mut EntityWorldMut, t: f32) {
+        let weight = match (&self.from, &self.to, self.lerp) {
+            (Some(from), Some(to), Some(lerp)) => {
+                camera.insert(lerp(from, to, t));
+                self.from_weight.lerp(self.to_weight(), t)
+            }
+            // Dips through no effect, swapping the parameters halfway.
+            (Some(_), Some(_), None) if t < 0.5 => self.from_weight * (1.0 - 2.0 * t),
+            (Some(_), Some(_), None) => {
+                self.show_target(camera);
+                self.to_weight() * (2.0 * t - 1.0)
+            }
+            (Some(_), None, _) => self.from_weight * (1.0 - t),
+            (None, Some(_), _) => {
+                self.show_target(camera);
+                self.to_weight() * t
+            }
+            (None, None, _) => return,
+        };
+
+        camera.insert(EffectWeight::<T>::new(weight.clamp(0.0, 1.0)));
+    }
+
+    fn finish(&mut self, camera: &mut EntityWorldMut) {
+        match &self.to {
+            Some(to) => camera.insert(to.clone()),
+            None => camera.remove::<T>(),
+        };
+        match self.weight {
+            Some(weight) => camera.insert(weight),
+            None => camera.remove::<EffectWeight<T>>(),
+        };
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+type Targets = HashMap<TypeId, Box<dyn Any + Send + Sync>>;
+
+/// Interpolates an effect's parameters, and tells whether two values can be interpolated.
+type Interpolate<T> = (fn(&T, &T, f32) -> T, fn(&T, &T) -> bool);
+
+type StartTrack = fn(
+    &EntityWorldMut,
+    &mut Targets,
+    Option<&ActiveTransition>,
+) -> Option<Box<dyn TransitionTrack>>;
+
+fn start_track<T: Component + Clone>(
+    camera: &EntityWorldMut,
+    targets: &mut Targets,
+    interrupted: Option<&ActiveTransition>,
+    interpolate: Option<Interpolate<T>>,
+) -> Option<Box<dyn TransitionTrack>> {
+    let from = camera.get::<T>().cloned();
+    let to = targets
+        .remove(&TypeId::of::<T>())
+        .and_then(|target| target.downcast::<T>().ok())
+        .map(|to| *to);
+    if from.is_none() && to.is_none() {
+        return None;
+    }
+
+    // Values which can not be interpolated, e.g. masks of different variants, fade instead.
+    let lerp = interpolate
+        .filter(|(_, can_lerp)| match (&from, &to) {
+            (Some(from), Some(to)) => can_lerp(from, to),
+            _ => true,
+        })
+        .map(|(lerp, _)| lerp);
+
+    let shown_weight = camera.get::<EffectWeight<T>>().copied();
+    let weight = match interrupted.and_then(|transition| {
+        transition
+            .tracks
+            .iter()
+            .find_map(|track| track.as_any().downcast_ref::<EffectTrack<T>>())
+    }) {
+        Some(track) => track.weight,
+        None => shown_weight,
+    };
+
+    Some(Box::new(EffectTrack {
+        from,
+        to,
+        from_weight: shown_weight.map_or(1.0, |weight| weight.weight),
+        weight,
+        lerp,
+        showing_target: false,
+    }))
+}
+
+/// The effects which take part in [`StackTransition`]s.
+#[derive(Resource, Default)]
+struct TransitionEffects {
+    start_tracks: Vec<StartTrack>,
+}
+
+fn start_stack_transitions(world: &mut World) {
+    let cameras = world
+        .query_filtered::<Entity, With<StackTransition>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    let start_tracks = world.resource::<TransitionEffects>().start_tracks.clone();
+
+    for camera in cameras {
+        let mut entity = world.entity_mut(camera);
+        let Some(mut transition) = entity.take::<StackTransition>() else {
+            continue;
+        };
+        let interrupted = entity.take::<ActiveTransition>();
+
+        let tracks = start_tracks
+            .iter()
+            .filter_map(|start| start(&entity, &mut transition.targets, interrupted.as_ref()))
+            .collect();
+        if !transition.targets.is_empty() {
+            warn!(
+                "{} effects without an `EffectTransitionPlugin` were left out of a transition",
+                transition.targets.len()
+            );
+        }
+
+        if let Some(order) = transition.order.take() {
+            entity.insert(order);
+        }
+        entity.insert(ActiveTransition {
+            tracks,
+            duration: transition.duration,
+            ease: transition.ease,
+            elapsed: 0.0,
+        });
+    }
+}
+
+fn run_stack_transitions(world: &mut World) {
+    let delta = world.resource::<Time>().delta_secs();
+    let cameras = world
+        .query_filtered::<Entity, With<ActiveTransition>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+
+    for camera in cameras {
+        let mut entity = world.entity_mut(camera);
+        // Taken out of the camera, since the tracks change the camera's other components.
+        let Some(mut transition) = entity.take::<ActiveTransition>() else {
+            continue;
+        };
+
+        transition.elapsed += delta;
+        let t = EasingCurve::new(0.0, 1.0, transition.ease).sample_clamped(transition.progress());
+        let done = transition.progress() >= 1.0;
+
+        for track in &mut transition.tracks {
+            match done {
+                true => track.finish(&mut entity),
+                false => track.update(&mut entity, t),
+            }
+        }
+
+        match done {
+            true => {
+                world.send_event(StackTransitionCompleted { camera });
+            }
+            false => {
+                entity.insert(transition);
+            }
+        }
+    }
+}
+
+/// Lets the effect `T` take part in [`StackTransition`]s.
+///
+/// Added for the effects of this crate by [`super::PostProcessingPlugin`].
+/// Add it for custom effects, interpolating those implementing [`EffectLerp`].
+pub struct EffectTransitionPlugin<T> {
+    start_track: StartTrack,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Component + Clone> EffectTransitionPlugin<T> {
+    /// Transitions the effect by fading it out and back in with the new parameters.
+    pub fn fading() -> Self {
+        Self {
+            start_track: |camera, targets, interrupted| {
+                start_track::<T>(camera, targets, interrupted, None)
+            },
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: EffectLerp> EffectTransitionPlugin<T> {
+    /// Transitions the effect by interpolating its parameters.
+    pub fn interpolating() -> Self {
+        Self {
+            start_track: |camera, targets, interrupted| {
+                start_track::<T>(camera, targets, interrupted, Some((T::lerp, T::can_lerp)))
+            },
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static> Plugin for EffectTransitionPlugin<T> {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<TransitionEffects>() {
+            app.init_resource::<TransitionEffects>()
+                .add_event::<StackTransitionCompleted>()
+                .add_systems(
+                    Update,
+                    (start_stack_transitions, run_stack_transitions).chain(),
+                );
+        }
+
+        app.world_mut()
+            .resource_mut::<TransitionEffects>()
+            .start_tracks
+            .push(self.start_track);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::post_processing::masks::{Mask, MaskVariant};
+
+    fn advance(app: &mut App, seconds: f32) {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(seconds));
+        app.update();
+    }
+
+    #[test]
+    fn masks_of_different_variants_fade() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins(EffectTransitionPlugin::<Mask>::interpolating());
+        let camera = app.world_mut().spawn(Mask::vignette()).id();
+        app.world_mut()
+            .entity_mut(camera)
+            .insert(StackTransition::new(1.0).with(Mask::crt()));
+        app.update();
+
+        // Fading out the vignette as it is, instead of raising its strength towards the CRT's.
+        advance(&mut app, 0.25);
+        let camera_ref = app.world().entity(camera);
+        let mask = camera_ref.get::<Mask>().expect("Should keep the mask");
+        assert_eq!(mask.variant, MaskVariant::Vignette);
+        assert_eq!(mask.strength, Mask::vignette().strength);
+        let weight = camera_ref
+            .get::<EffectWeight<Mask>>()
+            .expect("Should fade the mask");
+        assert_eq!(weight.weight, 0.5);
+
+        // Fading in the CRT mask as it will be.
+        advance(&mut app, 0.5);
+        let camera_ref = app.world().entity(camera);
+        let mask = camera_ref.get::<Mask>().expect("Should keep the mask");
+        assert_eq!(mask.variant, MaskVariant::Crt);
+        assert_eq!(mask.strength, Mask::crt().strength);
+        let weight = camera_ref
+            .get::<EffectWeight<Mask>>()
+            .expect("Should fade the mask");
+        assert_eq!(weight.weight, 0.5);
+    }
+}
@@ -0,0 +1,265 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+/// Effect parameters which can be interpolated, so an [`EffectTween`] can animate them.
+pub trait EffectLerp: Component + Clone {
+    /// The parameters a fraction `t` of the way from `self` to `other`.
+    ///
+    /// `t` is usually within `0.0..=1.0`, but may leave that range for easing functions
+    /// which overshoot, such as [`EaseFunction::BackOut`].
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+
+    /// Whether [`Self::lerp`] gets from `self` to `other` without a jump, e.g. false for
+    /// masks of different variants.
+    ///
+    /// A [`super::transition::StackTransition`] fades such effects out and back in instead.
+    fn can_lerp(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// A value of an [`EffectTween`] and how it is reached from the previous one.
+#[derive(Debug, Clone)]
+pub struct Keyframe<T> {
+    /// The effect parameters at this keyframe.
+    pub value: T,
+    /// Seconds it takes to reach this keyframe from the previous one.
+    pub duration: f32,
+    /// The easing from the previous keyframe to this one.
+    pub ease: EaseFunction,
+}
+
+/// What an [`EffectTween`] does when it reaches its last keyframe.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TweenRepeat {
+    /// Stop at the last keyframe.
+    #[default]
+    Once,
+    /// Jump back to the first keyframe and play again.
+    Loop,
+    /// Play the keyframes backwards to the first, then forwards again.
+    PingPong,
+}
+
+/// Animates the effect `T` on the same entity through a sequence of keyframes.
+///
+/// ```rust,ignore
+/// // Fade to blur over half a second.
+/// commands.entity(camera).insert(
+///     EffectTween::new(Blur { amount: 0.0, ..default() })
+///         .then(Blur { amount: 1.0, ..default() }, 0.5, EaseFunction::CubicOut),
+/// );
+/// ```
+///
+/// If the entity does not have the effect yet, it is inserted at the tween's start.
+/// The tween follows [`Time`], so it pauses and scales with the game.
+/// An [`EffectTweenCompleted`] event is sent when it finishes, or completes a cycle when repeating.
+#[derive(Debug, Clone, Component)]
+pub struct EffectTween<T: EffectLerp> {
+    start: T,
+    keyframes: Vec<Keyframe<T>>,
+    /// What happens after the last keyframe.
+    pub repeat: TweenRepeat,
+    /// Whether the tween is stopped.
+    pub paused: bool,
+    elapsed: f32,
+    finished: bool,
+}
+
+impl<T: EffectLerp> EffectTween<T> {
+    /// A tween starting at the given effect parameters.
+    pub fn new(start: T) -> Self {
+        Self {
+            start,
+            keyframes: vec![],
+            repeat: TweenRepeat::Once,
+            paused: false,
+            elapsed: 0.0,
+            finished: false,
+        }
+    }
+
+    /// Adds a keyframe reached `duration` seconds after the previous one.
+    pub fn then(mut self, value: T, duration: f32, ease: EaseFunction) -> Self {
+        self.keyframes.push(Keyframe {
+            value,
+            duration: duration.max(0.0),
+            ease,
+        });
+        self
+    }
+
+    /// Plays the keyframes repeatedly.
+    pub fn looping(mut self) -> Self {
+        self.repeat = TweenRepeat::Loop;
+        self
+    }
+
+    /// Plays the keyframes back and forth.
+    pub fn ping_pong(mut self) -> Self {
+        self.repeat = TweenRepeat::PingPong;
+        self
+    }
+
+    /// The keyframes after the start.
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keyframes
+    }
+
+    /// Seconds it takes to play all keyframes once.
+    pub fn duration(&self) -> f32 {
+        self.keyframes
+            .iter()
+            .map(|keyframe| keyframe.duration)
+            .sum()
+    }
+
+    /// Seconds the tween has played.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Whether the tween reached its end. Repeating tweens never do.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Plays the tween from the start again.
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.finished = false;
+    }
+
+    /// The effect parameters the given seconds into the tween, ignoring repetition.
+    pub fn sample(&self, time: f32) -> T {
+        let mut from = &self.start;
+        let mut time = time.max(0.0);
+
+        for keyframe in &self.keyframes {
+            if time < keyframe.duration {
+                let t = EasingCurve::new(0.0, 1.0, keyframe.ease)
+                    .sample_clamped(time / keyframe.duration);
+                return from.lerp(&keyframe.value, t);
+            }
+            time -= keyframe.duration;
+            from = &keyframe.value;
+        }
+
+        from.clone()
+    }
+
+    /// Advances the tween by the given seconds.
+    ///
+    /// Returns the effect parameters at the new time,
+    /// and the number of times the tween finished or completed a cycle.
+    pub fn tick(&mut self, delta: f32) -> (T, u32) {
+        let duration = self.duration();
+        if self.paused || self.finished {
+            return (self.sample(self.position(duration)), 0);
+        }
+        if duration <= 0.0 {
+            // Nothing to animate, even when repeating.
+            self.finished = true;
+            return (self.sample(0.0), 1);
+        }
+
+        let before = self.elapsed;
+        self.elapsed += delta.max(0.0);
+
+        let completed = match self.repeat {
+            TweenRepeat::Once => {
+                self.finished = self.elapsed >= duration;
+                self.finished as u32
+            }
+            TweenRepeat::Loop => ((self.elapsed / duration) as u32) - ((before / duration) as u32),
+            TweenRepeat::PingPong => {
+                let cycle = 2.0 * duration;
+                ((self.elapsed / cycle) as u32) - ((before / cycle) as u32)
+            }
+        };
+
+        (self.sample(self.position(duration)), completed)
+    }
+
+    /// Where in the keyframes the elapsed time is, accounting for repetition.
+    fn position(&self, duration: f32) -> f32 {
+        if duration <= 0.0 {
+            return 0.0;
+        }
+
+        match self.repeat {
+            TweenRepeat::Once => self.elapsed.min(duration),
+            TweenRepeat::Loop => self.elapsed.rem_euclid(duration),
+            TweenRepeat::PingPong => {
+                let position = self.elapsed.rem_euclid(2.0 * duration);
+                if position > duration {
+                    2.0 * duration - position
+                } else {
+                    position
+                }
+            }
+        }
+    }
+}
+
+/// Sent when an [`EffectTween`] finishes, or completes a cycle when repeating.
+#[derive(Debug, Event)]
+pub struct EffectTweenCompleted<T> {
+    /// The entity the tween is on.
+    pub entity: Entity,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> EffectTweenCompleted<T> {
+    fn new(entity: Entity) -> Self {
+        Self {
+            entity,
+            marker: PhantomData,
+        }
+    }
+}
+
+fn tick_effect_tweens<T: EffectLerp>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut completed: EventWriter<EffectTweenCompleted<T>>,
+    mut tweens: Query<(Entity, &mut EffectTween<T>, Option<&mut T>)>,
+) {
+    for (entity, mut tween, effect) in &mut tweens {
+        let Some(mut effect) = effect else {
+            commands.entity(entity).insert(tween.sample(0.0));
+            continue;
+        };
+
+        if tween.paused || tween.finished {
+            continue;
+        }
+
+        let (value, completions) = tween.tick(time.delta_secs());
+        *effect = value;
+
+        for _ in 0..completions {
+            completed.send(EffectTweenCompleted::new(entity));
+        }
+    }
+}
+
+/// Animates the effect `T` via [`EffectTween<T>`].
+///
+/// Added for the effects of this crate by [`super::PostProcessingPlugin`].
+/// Add it for custom effects implementing [`EffectLerp`].
+pub struct EffectTweenPlugin<T: EffectLerp>(PhantomData<T>);
+
+impl<T: EffectLerp> Default for EffectTweenPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: EffectLerp> Plugin for EffectTweenPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EffectTweenCompleted<T>>()
+            .add_systems(Update, tick_effect_tweens::<T>);
+    }
+}
@@ -0,0 +1,287 @@
+use bevy::{
+    core_pipeline::prepass::{
+        DepthPrepass, MotionVectorPrepass, NormalPrepass, ViewPrepassTextures,
+    },
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_resource::{
+            binding_types::{
+                texture_2d, texture_2d_multisampled, texture_depth_2d,
+                texture_depth_2d_multisampled, uniform_buffer,
+            },
+            *,
+        },
+        renderer::RenderDevice,
+        view::{ViewUniform, ViewUniformOffset, ViewUniforms},
+        Render, RenderApp, RenderSet,
+    },
+    utils::HashMap,
+};
+
+use super::shader::{embedded_shader_path, load_shader_module};
+
+/// Which prepass textures a camera has, and so which are bound for effects reading the view.
+///
+/// Effects opt in to reading the view via e.g.
+/// [`super::simple_post_process::SimplePostProcess::view_bindings`].
+/// Their shaders then import the bindings from the `bevy_vfx_bag::view_bindings` module,
+/// where the shader defs `DEPTH_PREPASS`, `NORMAL_PREPASS` and `MOTION_VECTOR_PREPASS`
+/// tell which prepass textures exist:
+///
+/// ```wgsl
+/// #import bevy_vfx_bag::view_bindings::{view, prepass_depth}
+/// ```
+///
+/// Add [`DepthPrepass`], [`NormalPrepass`] or [`MotionVectorPrepass`] to a camera
+/// to give its effects the matching texture.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, Component)]
+pub struct ViewBindingsKey {
+    /// Whether the depth prepass texture is bound.
+    pub depth: bool,
+    /// Whether the normal prepass texture is bound.
+    pub normal: bool,
+    /// Whether the motion vector prepass texture is bound.
+    pub motion_vectors: bool,
+    /// Whether the prepass textures are multisampled.
+    pub multisampled: bool,
+}
+
+impl ViewBindingsKey {
+    /// All possible keys.
+    pub(crate) fn all() -> impl Iterator<Item = Self> {
+        (0..16u8).map(|bits| Self {
+            depth: bits & 1 != 0,
+            normal: bits & 2 != 0,
+            motion_vectors: bits & 4 != 0,
+            multisampled: bits & 8 != 0,
+        })
+    }
+
+    /// The shader defs for a pipeline binding the view at the given group.
+    pub fn shader_defs(&self, group: u32) -> Vec<ShaderDefVal> {
+        let mut shader_defs = vec![ShaderDefVal::UInt("VIEW_BINDINGS_GROUP".into(), group)];
+
+        if self.depth {
+            shader_defs.push("DEPTH_PREPASS".into());
+        }
+        if self.normal {
+            shader_defs.push("NORMAL_PREPASS".into());
+        }
+        if self.motion_vectors {
+            shader_defs.push("MOTION_VECTOR_PREPASS".into());
+        }
+        if self.multisampled {
+            shader_defs.push("MULTISAMPLED".into());
+        }
+
+        shader_defs
+    }
+}
+
+impl ExtractComponent for ViewBindingsKey {
+    type QueryData = (
+        Has<DepthPrepass>,
+        Has<NormalPrepass>,
+        Has<MotionVectorPrepass>,
+        Option<&'static Msaa>,
+    );
+    type QueryFilter = With<Camera>;
+    type Out = Self;
+
+    fn extract_component(
+        (depth, normal, motion_vectors, msaa): QueryItem<'_, Self::QueryData>,
+    ) -> Option<Self::Out> {
+        Some(Self {
+            depth,
+            normal,
+            motion_vectors,
+            multisampled: msaa.copied().unwrap_or_default().samples() > 1,
+        })
+    }
+}
+
+/// The bind group layouts of the view bindings, one per [`ViewBindingsKey`].
+#[derive(Resource, Clone)]
+pub struct ViewBindingsLayouts {
+    layouts: HashMap<ViewBindingsKey, BindGroupLayout>,
+}
+
+impl ViewBindingsLayouts {
+    /// The layout for the given key.
+    pub fn get(&self, key: ViewBindingsKey) -> &BindGroupLayout {
+        &self.layouts[&key]
+    }
+
+    /// Adds the view bindings for the given key to a pipeline's layouts and shader defs.
+    ///
+    /// The view is bound at the group following the given layouts.
+    pub(crate) fn specialize(
+        &self,
+        key: ViewBindingsKey,
+        layouts: &mut Vec<BindGroupLayout>,
+        shader_defs: &mut Vec<ShaderDefVal>,
+    ) {
+        shader_defs.extend(key.shader_defs(layouts.len() as u32));
+        layouts.push(self.get(key).clone());
+    }
+}
+
+impl FromWorld for ViewBindingsLayouts {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let visibility = ShaderStages::FRAGMENT | ShaderStages::COMPUTE;
+
+        let layouts = ViewBindingsKey::all()
+            .map(|key| {
+                let mut entries = vec![uniform_buffer::<ViewUniform>(true).build(0, visibility)];
+
+                if key.depth {
+                    let depth = match key.multisampled {
+                        true => texture_depth_2d_multisampled(),
+                        false => texture_depth_2d(),
+                    };
+                    entries.push(depth.build(1, visibility));
+                }
+                for (binding, bound) in [(2, key.normal), (3, key.motion_vectors)] {
+                    if !bound {
+                        continue;
+                    }
+                    let texture = match key.multisampled {
+                        true => {
+                            texture_2d_multisampled(TextureSampleType::Float { filterable: false })
+                        }
+                        false => texture_2d(TextureSampleType::Float { filterable: false }),
+                    };
+                    entries.push(texture.build(binding, visibility));
+                }
+
+                let layout =
+                    render_device.create_bind_group_layout("view_bindings_layout", &entries);
+                (key, layout)
+            })
+            .collect();
+
+        Self { layouts }
+    }
+}
+
+/// The view bind group of a specific view, used by effects reading the view.
+#[derive(Component)]
+pub struct ViewBindings {
+    bind_group: BindGroup,
+    offset: u32,
+}
+
+impl ViewBindings {
+    /// The bind group.
+    pub(crate) fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+
+    /// The dynamic offset of the view's uniform.
+    pub(crate) fn offset(&self) -> u32 {
+        self.offset
+    }
+}
+
+fn prepare_view_bindings(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    layouts: Res<ViewBindingsLayouts>,
+    view_uniforms: Res<ViewUniforms>,
+    views: Query<(
+        Entity,
+        &ViewBindingsKey,
+        &ViewUniformOffset,
+        Option<&ViewPrepassTextures>,
+    )>,
+) {
+    let Some(view_binding) = view_uniforms.uniforms.binding() else {
+        return;
+    };
+
+    for (entity, key, offset, prepass_textures) in &views {
+        let textures = [
+            (1, key.depth, prepass_textures.and_then(|t| t.depth_view())),
+            (
+                2,
+                key.normal,
+                prepass_textures.and_then(|t| t.normal_view()),
+            ),
+            (
+                3,
+                key.motion_vectors,
+                prepass_textures.and_then(|t| t.motion_vectors_view()),
+            ),
+        ];
+
+        let mut entries = vec![BindGroupEntry {
+            binding: 0,
+            resource: view_binding.clone(),
+        }];
+        let mut complete = true;
+        for (binding, bound, view) in textures {
+            match (bound, view) {
+                (false, _) => {}
+                (true, Some(view)) => entries.push(BindGroupEntry {
+                    binding,
+                    resource: view.into_binding(),
+                }),
+                // The prepass textures are not there yet.
+                (true, None) => complete = false,
+            }
+        }
+
+        if !complete {
+            commands.entity(entity).remove::<ViewBindings>();
+            continue;
+        }
+
+        commands.entity(entity).insert(ViewBindings {
+            bind_group: render_device.create_bind_group(
+                "view_bindings_bind_group",
+                layouts.get(*key),
+                &entries,
+            ),
+            offset: offset.offset,
+        });
+    }
+}
+
+/// Binds the view uniform and prepass textures for effects reading the view.
+///
+/// Added by the effect plugins of effects reading the view, so it does not need to be added manually.
+pub(crate) struct ViewBindingsPlugin;
+
+impl Plugin for ViewBindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ExtractComponentPlugin::<ViewBindingsKey>::default());
+        load_shader_module(app, embedded_shader_path("view_bindings.wgsl"));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.add_systems(
+            Render,
+            prepare_view_bindings.in_set(RenderSet::PrepareBindGroups),
+        );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<ViewBindingsLayouts>();
+    }
+}
+
+/// Adds the [`ViewBindingsPlugin`], unless it was added already.
+pub(crate) fn add_view_bindings(app: &mut App) {
+    if !app.is_plugin_added::<ViewBindingsPlugin>() {
+        app.add_plugins(ViewBindingsPlugin);
+    }
+}
@@ -5,14 +5,27 @@ use bevy::{
 
 //use std::fmt::Display;
 
-use super::simple_post_process::SimplePostProcess;
+use super::{
+    fusion::{FusionStage, FusionStageKind},
+    impulse::ImpulseEffect,
+    shader::embedded_shader_path,
+    simple_post_process::SimplePostProcess,
+    tween::EffectLerp,
+};
 
 ///TODO
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel, Default)]
 pub struct WavePostProcessLabel;
 
 /// Pixelate settings.
-#[derive(Default, Component, Clone, Copy, ExtractComponent, ShaderType)]
+#[derive(Default, Component, Clone, Copy, ExtractComponent, ShaderType, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub struct Wave {
     /// How many waves in the x axis.
     pub waves_x: f32,
@@ -33,6 +46,29 @@ pub struct Wave {
     pub amplitude_y: f32,
 }
 
+impl EffectLerp for Wave {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            waves_x: self.waves_x.lerp(other.waves_x, t),
+            waves_y: self.waves_y.lerp(other.waves_y, t),
+            speed_x: self.speed_x.lerp(other.speed_x, t),
+            speed_y: self.speed_y.lerp(other.speed_y, t),
+            amplitude_x: self.amplitude_x.lerp(other.amplitude_x, t),
+            amplitude_y: self.amplitude_y.lerp(other.amplitude_y, t),
+        }
+    }
+}
+
+impl ImpulseEffect for Wave {
+    fn at_rest(&self) -> Self {
+        Self {
+            amplitude_x: 0.0,
+            amplitude_y: 0.0,
+            ..*self
+        }
+    }
+}
+
 // impl Display for Wave {
 //     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 //         write!(f, "Pixelate block size: {}", self.block_size)
@@ -41,7 +77,17 @@ pub struct Wave {
 
 impl SimplePostProcess for Wave {
     fn shader_path() -> String {
-        concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/", "wave.wgsl").into()
+        embedded_shader_path("wave.wgsl")
     }
     type Label = WavePostProcessLabel;
+
+    fn fusion_stage() -> Option<FusionStage> {
+        Some(FusionStage {
+            module: embedded_shader_path("stages/wave.wgsl"),
+            import_path: "bevy_vfx_bag::wave",
+            uniform: "Wave",
+            function: "wave_uv",
+            kind: FusionStageKind::Uv,
+        })
+    }
 }
@@ -0,0 +1,338 @@
+//! Golden-image tests of the CPU reference effects.
+//!
+//! Run with `UPDATE_GOLDEN=1` to write the golden images after an intended change,
+//! and look at the changed images before committing them.
+
+use std::path::PathBuf;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use bevy_vfx_bag::post_processing::{
+    blur::Blur, chromatic_aberration::ChromaticAberration, flip::Flip, lut::Lut, masks::Mask,
+    pixelate::Pixelate, raindrops::Raindrops, reference, wave::Wave,
+};
+use image::{Rgba, RgbaImage};
+
+/// A pattern with gradients, hard edges and an odd size, to make UV mistakes visible.
+fn input() -> RgbaImage {
+    RgbaImage::from_fn(67, 45, |x, y| {
+        let checker = ((x / 8 + y / 8) % 2) as u8;
+        Rgba([
+            (x * 255 / 66) as u8,
+            (y * 255 / 44) as u8,
+            checker * 200 + 30,
+            255,
+        ])
+    })
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.png"))
+}
+
+/// The largest difference of any channel of any pixel.
+fn max_difference(a: &RgbaImage, b: &RgbaImage) -> u8 {
+    a.pixels()
+        .zip(b.pixels())
+        .flat_map(|(a, b)| a.0.into_iter().zip(b.0).map(|(a, b)| a.abs_diff(b)))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Compares against the golden image, allowing for rounding differences between platforms.
+fn assert_golden(name: &str, actual: &RgbaImage) {
+    let path = golden_path(name);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        actual.save(&path).expect("Should write golden image");
+        return;
+    }
+
+    let golden = image::open(&path)
+        .unwrap_or_else(|error| {
+            panic!(
+                "Should read golden image {}, run with `UPDATE_GOLDEN=1` to create it: {error}",
+                path.display()
+            )
+        })
+        .to_rgba8();
+
+    assert_eq!(golden.dimensions(), actual.dimensions(), "{name}");
+    let difference = max_difference(&golden, actual);
+    assert!(
+        difference <= 1,
+        "{name} differs from its golden image by {difference}"
+    );
+}
+
+fn neutral_lut() -> Image {
+    Lut::image_from_png(include_bytes!("../assets/luts/neutral.png"))
+}
+
+#[test]
+fn blur() {
+    let blur = Blur {
+        amount: 0.8,
+        kernel_radius: 0.02,
+    };
+    assert_golden("blur", &reference::blur(&input(), &blur));
+}
+
+#[test]
+fn pixelate() {
+    let pixelate = Pixelate { block_size: 6.0 };
+    assert_golden("pixelate", &reference::pixelate(&input(), &pixelate));
+}
+
+#[test]
+fn flip() {
+    for (name, flip) in [
+        ("flip_horizontal", Flip::Horizontal),
+        ("flip_vertical", Flip::Vertical),
+        ("flip_horizontal_vertical", Flip::HorizontalVertical),
+    ] {
+        assert_golden(name, &reference::flip(&input(), &flip));
+    }
+}
+
+#[test]
+fn mask() {
+    for (name, mask) in [
+        ("mask_square", Mask::square()),
+        ("mask_crt", Mask::crt()),
+        ("mask_vignette", Mask::vignette()),
+    ] {
+        assert_golden(name, &reference::mask(&input(), &mask));
+    }
+}
+
+#[test]
+fn lut() {
+    let arctic = Lut::image_from_png(include_bytes!("../assets/luts/arctic.png"));
+    assert_golden("lut_arctic", &reference::lut(&input(), &arctic));
+}
+
+#[test]
+fn chromatic_aberration() {
+    let ca = ChromaticAberration {
+        magnitude_r: 0.03,
+        magnitude_g: 0.02,
+        magnitude_b: 0.04,
+        ..default()
+    };
+    assert_golden(
+        "chromatic_aberration",
+        &reference::chromatic_aberration(&input(), &ca),
+    );
+}
+
+#[test]
+fn wave() {
+    let wave = Wave {
+        waves_x: 3.0,
+        waves_y: 2.0,
+        speed_x: 1.0,
+        speed_y: 0.5,
+        amplitude_x: 0.05,
+        amplitude_y: 0.03,
+    };
+    assert_golden("wave", &reference::wave(&input(), &wave, 0.25));
+}
+
+/// A raindrops texture of drops in a grid, half of them animated.
+///
+/// The golden image uses it instead of the shipped texture, so it only changes with the effect.
+fn raindrops_texture() -> Image {
+    let size = 16;
+    let data = (0..size * size)
+        .flat_map(|i| {
+            let (x, y) = (i % size, i / size);
+            // Drops warp towards their center, and the animated ones have staggered phases.
+            let (cell_x, cell_y) = (x % 8, y % 8);
+            let animated = (x / 8 + y / 8) % 2 == 0;
+            [
+                (cell_x * 255 / 7) as u8,
+                (cell_y * 255 / 7) as u8,
+                ((x * 31 + y * 17) % 256) as u8,
+                if animated { 224 } else { 32 },
+            ]
+        })
+        .collect();
+
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::default(),
+    )
+}
+
+#[test]
+fn raindrops() {
+    let input = input();
+    let raindrops = Raindrops {
+        zoom: 0.5,
+        ..default()
+    };
+    let output = reference::raindrops_with_texture(&input, &raindrops, 0.25, &raindrops_texture());
+
+    // An identity golden would not test the warping at all.
+    assert!(
+        max_difference(&input, &output) > 0,
+        "raindrops did not change the image"
+    );
+    assert_golden("raindrops", &output);
+}
+
+#[test]
+fn neutral_lut_keeps_colors() {
+    let input = input();
+    let output = reference::lut(&input, &neutral_lut());
+
+    // The neutral look-up texture has 64 steps per channel.
+    let difference = max_difference(&input, &output);
+    assert!(
+        difference <= 6,
+        "neutral LUT changed colors by {difference}"
+    );
+}
+
+#[test]
+fn flipping_twice_keeps_image() {
+    let input = input();
+
+    for flip in [Flip::Horizontal, Flip::Vertical, Flip::HorizontalVertical] {
+        let output = reference::flip(&reference::flip(&input, &flip), &flip);
+        assert_eq!(max_difference(&input, &output), 0, "{flip}");
+    }
+}
+
+#[test]
+fn effects_at_rest_keep_image() {
+    // Not pixelate: its shader snaps UVs to texel edges, which rounding moves by a texel at times.
+    let input = input();
+    let outputs = [
+        reference::flip(&input, &Flip::None),
+        reference::blur(
+            &input,
+            &Blur {
+                amount: 0.0,
+                ..default()
+            },
+        ),
+        reference::mask(
+            &input,
+            &Mask {
+                fade: 1.0,
+                ..default()
+            },
+        ),
+        reference::chromatic_aberration(
+            &input,
+            &ChromaticAberration {
+                magnitude_r: 0.0,
+                magnitude_g: 0.0,
+                magnitude_b: 0.0,
+                ..default()
+            },
+        ),
+        reference::wave(
+            &input,
+            &Wave {
+                amplitude_x: 0.0,
+                amplitude_y: 0.0,
+                ..default()
+            },
+            1.0,
+        ),
+        reference::raindrops(
+            &input,
+            &Raindrops {
+                warping: 0.0,
+                ..default()
+            },
+            1.0,
+        ),
+    ];
+
+    for (index, output) in outputs.iter().enumerate() {
+        assert_eq!(max_difference(&input, output), 0, "effect {index}");
+    }
+}
+
+/// The share of pixels with a channel differing by more than rounding.
+fn differing_share(a: &RgbaImage, b: &RgbaImage) -> f32 {
+    let differing = a
+        .pixels()
+        .zip(b.pixels())
+        .filter(|(a, b)| a.0.into_iter().zip(b.0).any(|(a, b)| a.abs_diff(b) > 2))
+        .count();
+    differing as f32 / (a.width() * a.height()) as f32
+}
+
+/// Checks the fusion algorithm: `reference::fused` runs the steps the generated shader is
+/// written from, while the shader's text is checked in `fusion.rs` and validated by naga.
+#[test]
+fn fused_effects_match_unfused() {
+    use reference::FusedStage;
+
+    let input = input();
+    let time = 0.25;
+    // Waves move UVs past the edges, which flipping would mirror back in without clamping.
+    let wave = Wave {
+        waves_x: 3.0,
+        waves_y: 2.0,
+        amplitude_x: 0.08,
+        amplitude_y: 0.08,
+        ..default()
+    };
+    let flip = Flip::HorizontalVertical;
+    let pixelate = Pixelate { block_size: 6.0 };
+    let mask = Mask::vignette();
+
+    let runs = [
+        (
+            "flip+wave",
+            vec![FusedStage::Flip(flip), FusedStage::Wave(wave)],
+            reference::wave(&reference::flip(&input, &flip), &wave, time),
+        ),
+        (
+            "wave+flip+mask",
+            vec![
+                FusedStage::Wave(wave),
+                FusedStage::Flip(flip),
+                FusedStage::Mask(mask),
+            ],
+            reference::mask(
+                &reference::flip(&reference::wave(&input, &wave, time), &flip),
+                &mask,
+            ),
+        ),
+        (
+            "mask+pixelate",
+            vec![FusedStage::Mask(mask), FusedStage::Pixelate(pixelate)],
+            reference::pixelate(&reference::mask(&input, &mask), &pixelate),
+        ),
+    ];
+
+    for (name, stages, unfused) in runs {
+        let fused = reference::fused(&input, &stages, time);
+        // Fused effects sample their input between texels, which moves some pixels by a texel.
+        let share = differing_share(&fused, &unfused);
+        assert!(
+            share <= 0.02,
+            "fused {name} differs from unfused in {:.1}% of pixels",
+            share * 100.0
+        );
+    }
+}